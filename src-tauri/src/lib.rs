@@ -2,28 +2,95 @@ mod keyring;
 mod s3;
 
 use specta_typescript::{BigIntExportBehavior, Typescript};
-use tauri_specta::{collect_commands, Builder};
+use tauri::Manager;
+use tauri_specta::{collect_commands, collect_events, Builder};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let builder = Builder::<tauri::Wry>::new().commands(collect_commands![
-        s3::connect_to_s3,
-        s3::list_buckets,
-        s3::list_objects,
-        s3::download_object,
-        s3::download_objects,
-        s3::delete_objects,
-        s3::download_folder,
-        s3::delete_folder,
-        s3::upload_objects,
-        s3::create_folder,
-        s3::move_objects,
-        keyring::save_connection,
-        keyring::load_saved_connections,
-        keyring::delete_saved_connection,
-        keyring::is_connection_saved,
-        keyring::is_connection_duplicate,
-    ]);
+    let builder = Builder::<tauri::Wry>::new()
+        .commands(collect_commands![
+            s3::connect_to_s3,
+            s3::list_buckets,
+            s3::create_bucket,
+            s3::delete_bucket,
+            s3::empty_and_delete_bucket,
+            s3::list_objects,
+            s3::set_listing_cache_ttl,
+            s3::clear_listing_cache,
+            s3::list_objects_job,
+            s3::cancel_job,
+            s3::search_objects,
+            s3::calculate_folder_size,
+            s3::generate_bucket_report,
+            s3::export_bucket_report,
+            s3::find_duplicates,
+            s3::delete_duplicates,
+            s3::compare_locations,
+            s3::export_compare_report,
+            s3::copy_objects,
+            s3::import_inventory,
+            s3::list_inventories,
+            s3::delete_inventory,
+            s3::list_inventory_objects,
+            s3::search_inventory,
+            s3::generate_inventory_report,
+            s3::export_listing,
+            s3::index_bucket,
+            s3::list_indexed_buckets,
+            s3::remove_indexed_bucket,
+            s3::prune_indexed_buckets,
+            s3::search_index,
+            s3::get_object_details,
+            s3::update_object_metadata,
+            s3::presign_get_urls,
+            s3::presign_put_url,
+            s3::presign_post_policy,
+            s3::download_object,
+            s3::download_objects,
+            s3::delete_objects,
+            s3::download_folder,
+            s3::delete_folder,
+            s3::upload_objects,
+            s3::create_folder,
+            s3::move_objects,
+            s3::list_object_versions,
+            s3::download_object_version,
+            s3::restore_object_version,
+            s3::undelete_objects,
+            s3::list_objects_at,
+            s3::restore_prefix_to_time,
+            s3::purge_object_versions,
+            s3::cleanup_folder_markers,
+            keyring::save_connection,
+            keyring::load_saved_connections,
+            keyring::delete_saved_connection,
+            keyring::is_connection_saved,
+            keyring::is_connection_duplicate,
+        ])
+        .events(collect_events![
+            s3::ListObjectsBatch,
+            s3::ListObjectsFinished,
+            s3::SearchObjectsMatches,
+            s3::SearchObjectsFinished,
+            s3::FolderSizeProgress,
+            s3::FolderSizeFinished,
+            s3::BucketReportProgress,
+            s3::BucketReportFinished,
+            s3::DuplicateScanProgress,
+            s3::DuplicateScanFinished,
+            s3::CompareProgress,
+            s3::CompareFinished,
+            s3::CopyObjectsProgress,
+            s3::CopyObjectsFinished,
+            s3::InventoryImportProgress,
+            s3::InventoryImportFinished,
+            s3::ListingExportProgress,
+            s3::ListingExportFinished,
+            s3::IndexProgress,
+            s3::IndexFinished,
+            s3::EmptyBucketProgress,
+            s3::EmptyBucketFinished
+        ]);
 
     #[cfg(debug_assertions)]
     builder
@@ -38,11 +105,22 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_keyring::init())
         .manage(s3::ConnectionMap::default())
+        .manage(s3::JobMap::default())
+        .manage(s3::FolderSizeCache::default())
+        .manage(s3::ListingCache::default())
+        .manage(s3::BucketRegionCache::default())
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(builder.invoke_handler())
         .setup(move |app| {
             builder.mount_events(app);
 
+            // The index is optional, a failure here only shows up once it's used
+            let data_dir = app.path().app_data_dir()?;
+            if let Err(e) = std::fs::create_dir_all(&data_dir) {
+                eprintln!("Failed to create app data directory: {}", e);
+            }
+            app.manage(s3::ObjectIndex::new(data_dir.join("index.sqlite3")));
+
             Ok(())
         })
         .run(tauri::generate_context!())
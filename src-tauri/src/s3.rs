@@ -1,23 +1,76 @@
+mod cache;
+mod compare;
+mod duplicates;
+mod empty_bucket;
+mod export;
+mod index;
+mod inventory;
+mod jobs;
+mod metadata;
+mod presign;
+mod purge;
+mod region;
+mod report;
 mod s3_service;
+mod search;
+#[cfg(test)]
+mod test_support;
+mod url;
+mod usage;
+mod versions;
 
+use aws_sdk_s3::primitives::DateTime;
 use serde::{Deserialize, Serialize};
 use specta::Type;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::State;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager, State};
+use tauri_specta::Event;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
+pub use cache::ListingCache;
+pub use index::ObjectIndex;
+pub use jobs::JobMap;
+pub use region::BucketRegionCache;
 pub use s3_service::S3Service;
+pub use usage::FolderSizeCache;
 
-use crate::s3::s3_service::S3ServiceConfig;
+use crate::s3::cache::{ListingKey, Mutation};
+use crate::s3::compare::{CompareReport, ListingComparer};
+use crate::s3::duplicates::{DuplicateFinder, DuplicateReport, DuplicateSelection};
+use crate::s3::empty_bucket::EmptyBucketSummary;
+use crate::s3::export::{ListingFormat, ListingWriter};
+use crate::s3::index::{
+    IndexSearchResult, IndexedBucket, InventoryInfo, InventoryListing, InventoryReport,
+    InventorySearchResult, NewInventory,
+};
+use crate::s3::inventory::InventoryManifest;
+use crate::s3::jobs::JobHandle;
+use crate::s3::metadata::{MetadataChanges, MetadataTarget, MetadataUpdate};
+use crate::s3::presign::{
+    presign_expiry, PostPolicy, PresignedPost, PresignedUpload, PresignedUrl, ResponseOverrides,
+};
+use crate::s3::purge::{PurgePlanner, PurgeRules, PurgeSummary};
+use crate::s3::region::DEFAULT_REGION;
+use crate::s3::report::{BucketReport, ReportBuilder, ReportFormat};
+use crate::s3::s3_service::{move_destination_key, upload_key, S3ServiceConfig, DEFAULT_DELIMITER};
+use crate::s3::search::{SearchFilters, SearchMatcher};
+use crate::s3::usage::{FolderSize, UsageTotals};
+use crate::s3::versions::{
+    parse_timestamp, KeyState, ObjectVersion, PointInTime, PointInTimeRestore,
+};
 
 pub type ConnectionMap = Arc<Mutex<HashMap<String, ConnectionConfig>>>;
 
 #[derive(Serialize, Deserialize, Type, Clone)]
 pub struct Connection {
     id: String,
+    // Unlike `id`, which is new on every connect, stays the same for the same
+    // account and credentials. Data kept across sessions is keyed on it.
+    identity: String,
     label: String,
     provider: BucketProvider,
 }
@@ -27,6 +80,8 @@ pub struct CommonConfig {
     pub label: String,
     pub access_key_id: String,
     pub secret_access_key: String,
+    // CDN or custom domain serving the bucket root, used for object URLs
+    pub public_base_url: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Type, Clone)]
@@ -87,6 +142,58 @@ pub enum BucketProvider {
     Custom,
 }
 
+fn connection_identity(
+    provider: BucketProvider,
+    location: Option<&str>,
+    access_key_id: &str,
+) -> String {
+    match location {
+        Some(location) => format!("{:?}|{}|{}", provider, location, access_key_id),
+        None => format!("{:?}|{}", provider, access_key_id),
+    }
+}
+
+impl ConnectionConfig {
+    // Provider, account or endpoint and access key id, see Connection::identity
+    pub fn identity(&self) -> String {
+        match self {
+            ConnectionConfig::S3(s3) => {
+                connection_identity(BucketProvider::S3, None, &s3.common.access_key_id)
+            }
+            ConnectionConfig::R2(r2) => connection_identity(
+                BucketProvider::R2,
+                Some(&r2.account_id),
+                &r2.common.access_key_id,
+            ),
+            ConnectionConfig::Custom(custom) => connection_identity(
+                BucketProvider::Custom,
+                Some(&custom.endpoint_url),
+                &custom.common.access_key_id,
+            ),
+        }
+    }
+}
+
+impl SavedConnectionConfig {
+    pub fn identity(&self) -> String {
+        match self {
+            SavedConnectionConfig::S3(s3) => {
+                connection_identity(BucketProvider::S3, None, &s3.common.access_key_id)
+            }
+            SavedConnectionConfig::R2(r2) => connection_identity(
+                BucketProvider::R2,
+                Some(&r2.account_id),
+                &r2.common.access_key_id,
+            ),
+            SavedConnectionConfig::Custom(custom) => connection_identity(
+                BucketProvider::Custom,
+                Some(&custom.endpoint_url),
+                &custom.common.access_key_id,
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Type)]
 pub struct BucketInfo {
     pub provider: BucketProvider,
@@ -96,14 +203,86 @@ pub struct BucketInfo {
     pub creation_date: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Type)]
+// `Prefix` is a folder implied by the keys below it, `FolderMarker` is an
+// actual object whose key ends with the delimiter, e.g. one written by
+// create_folder. Listings don't look up the markers of subfolders, a marker
+// shows up in the listing of its own folder.
+#[derive(Debug, Serialize, Deserialize, Type, Clone, PartialEq)]
+pub enum ObjectKind {
+    File,
+    Prefix,
+    FolderMarker,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
 pub struct ObjectInfo {
     pub key: String,
     pub size: Option<i64>,
     pub last_modified: Option<String>,
     pub storage_class: Option<String>,
     pub is_folder: bool,
+    pub kind: ObjectKind,
     pub url: String,
+    pub etag: Option<String>,
+    pub checksum_algorithms: Vec<String>,
+    pub owner: Option<ObjectOwner>,
+    pub restore_status: Option<RestoreStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct ObjectOwner {
+    pub id: Option<String>,
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct RestoreStatus {
+    pub is_restore_in_progress: bool,
+    pub restore_expiry_date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct ObjectTag {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct ObjectChecksum {
+    pub algorithm: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct ObjectDetails {
+    pub key: String,
+    pub version_id: Option<String>,
+    pub content_type: Option<String>,
+    pub content_length: Option<i64>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub storage_class: Option<String>,
+    pub cache_control: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_language: Option<String>,
+    pub expires: Option<String>,
+    pub website_redirect_location: Option<String>,
+    pub metadata: HashMap<String, String>,
+    pub server_side_encryption: Option<String>,
+    pub sse_kms_key_id: Option<String>,
+    pub sse_customer_algorithm: Option<String>,
+    pub bucket_key_enabled: Option<bool>,
+    pub object_lock_mode: Option<String>,
+    pub object_lock_retain_until_date: Option<String>,
+    pub object_lock_legal_hold_status: Option<String>,
+    pub replication_status: Option<String>,
+    pub restore_status: Option<RestoreStatus>,
+    pub checksums: Vec<ObjectChecksum>,
+    pub parts_count: Option<i32>,
+    // None when the provider doesn't support object tagging or reading the
+    // tags is denied
+    pub tags: Option<Vec<ObjectTag>>,
 }
 
 #[derive(Serialize, Deserialize, Type)]
@@ -118,7 +297,9 @@ fn build_service_config(
 ) -> S3ServiceConfig {
     match config {
         ConnectionConfig::S3(s3_config) => {
-            let region = bucket_region.unwrap_or_else(|| "us-east-1".to_string());
+            let region = region::known_region(bucket_region.as_deref())
+                .unwrap_or(DEFAULT_REGION)
+                .to_string();
 
             S3ServiceConfig {
                 config: s3_config,
@@ -139,11 +320,15 @@ fn build_service_config(
                 provider: BucketProvider::R2,
             }
         }
+        // Servers like MinIO can be configured with a region and reject
+        // requests signed for another one
         ConnectionConfig::Custom(custom_config) => S3ServiceConfig {
             config: S3Config {
                 common: custom_config.common,
             },
-            region: "auto".to_string(),
+            region: region::known_region(bucket_region.as_deref())
+                .unwrap_or("auto")
+                .to_string(),
             endpoint_url: custom_config.endpoint_url,
             provider: BucketProvider::Custom,
         },
@@ -178,6 +363,99 @@ async fn create_service_from_config(
         .map_err(|e| format!("Failed to init S3 service: {}", e))
 }
 
+// Operations on a bucket have to go to the bucket's regional endpoint. S3
+// buckets the frontend has no region for are looked up once and cached.
+async fn create_bucket_service(
+    opts: &CommonOperationOptions,
+    bucket_name: &str,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<S3Service, String> {
+    let config = state
+        .lock()
+        .await
+        .get(&opts.connection.id)
+        .ok_or_else(|| "Connection not found".to_string())?
+        .clone();
+
+    let bucket_region = resolve_bucket_region(
+        &opts.connection.id,
+        &config,
+        opts.bucket_region.as_deref(),
+        bucket_name,
+        regions.inner(),
+    )
+    .await?;
+
+    create_service_from_config(config, bucket_region).await
+}
+
+async fn resolve_bucket_region(
+    connection_id: &str,
+    config: &ConnectionConfig,
+    known: Option<&str>,
+    bucket_name: &str,
+    regions: &BucketRegionCache,
+) -> Result<Option<String>, String> {
+    // R2 and custom endpoints serve every bucket from the same endpoint
+    if !matches!(config, ConnectionConfig::S3(_)) {
+        return Ok(known.map(|region| region.to_string()));
+    }
+
+    if let Some(region) = region::known_region(known) {
+        return Ok(Some(region.to_string()));
+    }
+
+    if let Some(region) = regions.get(connection_id, bucket_name).await {
+        return Ok(Some(region));
+    }
+
+    let service = create_service_from_config(config.clone(), None).await?;
+    let region = service
+        .detect_bucket_region(bucket_name)
+        .await
+        .map_err(|e| format!("Failed to detect bucket region: {}", e))?;
+
+    regions
+        .insert(connection_id, bucket_name, region.clone())
+        .await;
+
+    Ok(Some(region))
+}
+
+// Drops every cached listing and folder size that a mutation made stale.
+async fn invalidate_caches(
+    app: &AppHandle<tauri::Wry>,
+    opts: &CommonOperationOptions,
+    bucket_name: &str,
+    mutation: &Mutation,
+) {
+    let connection_id = &opts.connection.id;
+
+    app.state::<ListingCache>()
+        .invalidate(connection_id, bucket_name, mutation)
+        .await;
+    app.state::<FolderSizeCache>()
+        .invalidate(connection_id, bucket_name, mutation)
+        .await;
+
+    // Indexed buckets relist what changed on their next refresh. A failure
+    // here only makes that refresh less incremental than it could be.
+    let identity = opts.connection.identity.clone();
+    let bucket_name = bucket_name.to_string();
+    let prefixes: Vec<String> = mutation
+        .keys
+        .iter()
+        .chain(mutation.subtrees.iter())
+        .cloned()
+        .collect();
+
+    let _ = with_index(app.state::<ObjectIndex>().inner(), move |index| {
+        index.mark_dirty(&identity, &bucket_name, &prefixes)
+    })
+    .await;
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn connect_to_s3(
@@ -185,20 +463,24 @@ pub async fn connect_to_s3(
     state: State<'_, ConnectionMap>,
 ) -> Result<Connection, String> {
     let id = Uuid::new_v4().to_string();
+    let identity = config.identity();
 
     let connection = match &config {
         ConnectionConfig::S3(s3_config) => Connection {
             id: id.clone(),
+            identity,
             label: s3_config.common.label.clone(),
             provider: BucketProvider::S3,
         },
         ConnectionConfig::R2(r2_config) => Connection {
             id: id.clone(),
+            identity,
             label: r2_config.common.label.clone(),
             provider: BucketProvider::R2,
         },
         ConnectionConfig::Custom(custom_config) => Connection {
             id: id.clone(),
+            identity,
             label: custom_config.common.label.clone(),
             provider: BucketProvider::Custom,
         },
@@ -222,6 +504,7 @@ pub async fn connect_to_s3(
 pub async fn list_buckets(
     connection: Connection,
     state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
 ) -> Result<Vec<BucketInfo>, String> {
     let options = CommonOperationOptions {
         connection,
@@ -230,204 +513,3080 @@ pub async fn list_buckets(
 
     let service = create_s3_service(&options, state).await?;
 
-    service
+    let mut buckets = service
         .list_buckets()
         .await
-        .map_err(|e| format!("Failed to list buckets: {}", e))
+        .map_err(|e| format!("Failed to list buckets: {}", e))?;
+
+    // Not every S3 compatible ListBuckets reports regions. Buckets without
+    // one take the region detected earlier or are looked up now.
+    let mut undetected = Vec::new();
+
+    for (index, bucket) in buckets.iter_mut().enumerate() {
+        if bucket.provider != BucketProvider::S3 {
+            continue;
+        }
+
+        if !bucket.region.is_empty() {
+            regions
+                .insert(&options.connection.id, &bucket.name, bucket.region.clone())
+                .await;
+        } else if let Some(region) = regions.get(&options.connection.id, &bucket.name).await {
+            set_bucket_region(bucket, region);
+        } else {
+            undetected.push((index, bucket.name.clone()));
+        }
+    }
+
+    let mut undetected = undetected.into_iter();
+    let mut tasks = tokio::task::JoinSet::new();
+
+    loop {
+        while tasks.len() < REGION_DETECTION_CONCURRENCY {
+            let Some((index, bucket_name)) = undetected.next() else {
+                break;
+            };
+
+            let service = service.clone();
+
+            tasks.spawn(async move {
+                let region = service.detect_bucket_region(&bucket_name).await;
+                (index, region)
+            });
+        }
+
+        let Some(joined) = tasks.join_next().await else {
+            break;
+        };
+
+        // A bucket whose region can't be read is still listed, its region
+        // is looked up again when it's opened
+        if let Ok((index, Ok(region))) = joined {
+            let bucket = &mut buckets[index];
+
+            regions
+                .insert(&options.connection.id, &bucket.name, region.clone())
+                .await;
+            set_bucket_region(bucket, region);
+        }
+    }
+
+    Ok(buckets)
+}
+
+const REGION_DETECTION_CONCURRENCY: usize = 8;
+
+fn set_bucket_region(bucket: &mut BucketInfo, region: String) {
+    bucket.endpoint_url = url::bucket_url(&bucket.provider, "", &bucket.name, Some(&region));
+    bucket.region = region;
 }
 
 #[derive(Serialize, Deserialize, Type)]
-pub struct ListObjectsOptions {
-    common: CommonOperationOptions,
+pub struct CreateBucketOptions {
+    connection: Connection,
     bucket_name: String,
-    prefix: Option<String>,
+    // AWS region (defaults to us-east-1), R2 location hint or MinIO region
+    region: Option<String>,
+    object_lock: bool,
+    versioning: bool,
+    // Bucket policy JSON applied right after creation
+    policy: Option<String>,
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn list_objects(
-    opts: ListObjectsOptions,
+pub async fn create_bucket(
+    opts: CreateBucketOptions,
     state: State<'_, ConnectionMap>,
-) -> Result<Vec<ObjectInfo>, String> {
-    let service = create_s3_service(&opts.common, state).await?;
+    regions: State<'_, BucketRegionCache>,
+) -> Result<BucketInfo, String> {
+    let region = opts
+        .region
+        .map(|region| region.trim().to_string())
+        .filter(|region| !region.is_empty());
+    let policy = opts.policy.filter(|policy| !policy.trim().is_empty());
 
-    service
-        .list_objects(
+    if let Some(policy) = &policy {
+        serde_json::from_str::<serde_json::Value>(policy)
+            .map_err(|e| format!("Invalid bucket policy: {}", e))?;
+    }
+
+    // S3 only creates a bucket outside us-east-1 through that region's endpoint
+    let options = CommonOperationOptions {
+        connection: opts.connection,
+        bucket_region: region.clone(),
+    };
+    let service = create_s3_service(&options, state).await?;
+
+    let bucket = service
+        .create_bucket(
             &opts.bucket_name,
-            opts.prefix.as_deref(),
-            false,
-            opts.common.bucket_region,
+            region.as_deref(),
+            opts.object_lock,
+            opts.versioning,
+            policy.as_deref(),
         )
         .await
-        .map_err(|e| format!("Failed to list objects: {}", e))
+        .map_err(|e| format!("Failed to create bucket: {}", e))?;
+
+    if bucket.provider == BucketProvider::S3 {
+        regions
+            .insert(&options.connection.id, &bucket.name, bucket.region.clone())
+            .await;
+    }
+
+    Ok(bucket)
 }
 
 #[derive(Serialize, Deserialize, Type)]
-pub struct DownloadObjectOptions {
+pub struct DeleteBucketOptions {
     common: CommonOperationOptions,
     bucket_name: String,
-    key: String,
 }
 
-#[tauri::command]
-#[specta::specta]
-pub async fn download_object(
-    opts: DownloadObjectOptions,
-    state: State<'_, ConnectionMap>,
-) -> Result<Vec<u8>, String> {
-    let service = create_s3_service(&opts.common, state).await?;
+// Drops everything cached or indexed about a bucket that no longer exists
+async fn forget_bucket(
+    app: &AppHandle<tauri::Wry>,
+    common: &CommonOperationOptions,
+    bucket_name: &str,
+) {
+    invalidate_caches(app, common, bucket_name, &Mutation::subtree(String::new())).await;
+    app.state::<BucketRegionCache>()
+        .remove(&common.connection.id, bucket_name)
+        .await;
 
-    service
-        .download_object(&opts.bucket_name, &opts.key)
-        .await
-        .map_err(|e| format!("Failed to download object: {}", e))
-}
+    let identity = common.connection.identity.clone();
+    let bucket_name = bucket_name.to_string();
 
-#[derive(Serialize, Deserialize, Type)]
-pub struct DownloadObjectsOptions {
-    common: CommonOperationOptions,
-    bucket_name: String,
-    keys: Vec<String>,
+    let _ = with_index(app.state::<ObjectIndex>().inner(), move |index| {
+        index.remove_indexed_bucket(&identity, &bucket_name)
+    })
+    .await;
 }
 
+// Only deletes empty buckets, see empty_and_delete_bucket for the rest
 #[tauri::command]
 #[specta::specta]
-pub async fn download_objects(
-    opts: DownloadObjectsOptions,
+pub async fn delete_bucket(
+    app: AppHandle<tauri::Wry>,
+    opts: DeleteBucketOptions,
     state: State<'_, ConnectionMap>,
-) -> Result<Vec<(String, Vec<u8>)>, String> {
-    let service = create_s3_service(&opts.common, state).await?;
+    regions: State<'_, BucketRegionCache>,
+) -> Result<(), String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
 
     service
-        .download_objects(&opts.bucket_name, opts.keys)
+        .delete_bucket(&opts.bucket_name)
         .await
-        .map_err(|e| format!("Failed to download objects: {}", e))
+        .map_err(|e| format!("Failed to delete bucket: {}", e))?;
+
+    forget_bucket(&app, &opts.common, &opts.bucket_name).await;
+
+    Ok(())
 }
 
 #[derive(Serialize, Deserialize, Type)]
-pub struct DeleteObjectsOptions {
+pub struct EmptyAndDeleteBucketOptions {
     common: CommonOperationOptions,
     bucket_name: String,
-    keys: Vec<String>,
+    // Only counts what would be removed
+    dry_run: bool,
+    // Has to repeat the bucket name unless this is a dry run
+    confirmation: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct EmptyBucketProgress {
+    job_id: String,
+    summary: EmptyBucketSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct EmptyBucketFinished {
+    job_id: String,
+    summary: EmptyBucketSummary,
+    dry_run: bool,
+    bucket_deleted: bool,
+    errors: Vec<String>,
+    cancelled: bool,
+    error: Option<String>,
 }
 
+// Aborts incomplete multipart uploads, deletes every object, version and
+// delete marker page by page and finally the bucket itself. The bucket is
+// kept when anything couldn't be deleted.
 #[tauri::command]
 #[specta::specta]
-pub async fn delete_objects(
-    opts: DeleteObjectsOptions,
+pub async fn empty_and_delete_bucket(
+    app: AppHandle<tauri::Wry>,
+    opts: EmptyAndDeleteBucketOptions,
     state: State<'_, ConnectionMap>,
-) -> Result<(), String> {
-    let service = create_s3_service(&opts.common, state).await?;
+    regions: State<'_, BucketRegionCache>,
+    job_map: State<'_, JobMap>,
+) -> Result<String, String> {
+    if !opts.dry_run && opts.confirmation.as_deref() != Some(opts.bucket_name.as_str()) {
+        return Err("Type the bucket name to confirm the deletion".to_string());
+    }
 
-    service
-        .delete_objects(&opts.bucket_name, opts.keys)
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    // Guessing wrong would leave the old versions behind, so nothing is
+    // deleted unless the versioning state is known
+    let versioned = service
+        .is_versioned(&opts.bucket_name)
         .await
-        .map_err(|e| format!("Failed to delete objects: {}", e))
+        .map_err(|e| format!("Failed to read bucket versioning: {}", e))?;
+    let job = JobHandle::register(job_map.inner()).await;
+    let job_id = job.id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let bucket_name = opts.bucket_name.as_str();
+        let mut summary = EmptyBucketSummary {
+            versioned,
+            ..Default::default()
+        };
+        let mut errors = Vec::new();
+        let mut error = None;
+        let mut completed = false;
+
+        match service.list_multipart_uploads(bucket_name).await {
+            Ok(uploads) => {
+                for upload in uploads {
+                    if job.is_cancelled() {
+                        break;
+                    }
+
+                    if !opts.dry_run {
+                        if let Err(e) = service.abort_multipart_upload(bucket_name, &upload).await {
+                            errors.push(format!("{} ({}): {}", upload.key, upload.upload_id, e));
+                            continue;
+                        }
+                    }
+
+                    summary.multipart_upload_count += 1;
+                }
+            }
+            Err(e) => error = Some(format!("Failed to list multipart uploads: {}", e)),
+        }
+
+        if summary.versioned {
+            let mut pager = service.version_pager(bucket_name, None);
+
+            while error.is_none() && !job.is_cancelled() {
+                match pager.next_page().await {
+                    Ok(Some(versions)) => {
+                        let mut failed = Vec::new();
+
+                        if !opts.dry_run {
+                            match service.delete_object_versions(bucket_name, &versions).await {
+                                Ok(batch_failed) => failed = batch_failed,
+                                Err(e) => {
+                                    error =
+                                        Some(format!("Failed to delete object versions: {}", e));
+                                    break;
+                                }
+                            }
+                        }
+
+                        // The summary only counts what is actually gone
+                        let failed_versions: HashSet<(&str, Option<&str>)> = failed
+                            .iter()
+                            .map(|f| (f.key.as_str(), f.version_id.as_deref()))
+                            .collect();
+
+                        versions
+                            .iter()
+                            .filter(|v| {
+                                !failed_versions
+                                    .contains(&(v.key.as_str(), Some(v.version_id.as_str())))
+                            })
+                            .for_each(|v| summary.add_version(v));
+                        errors.extend(failed.iter().map(|f| f.to_string()));
+
+                        jobs::emit(
+                            &app,
+                            EmptyBucketProgress {
+                                job_id: job.id.clone(),
+                                summary: summary.clone(),
+                            },
+                        );
+                    }
+                    Ok(None) => {
+                        completed = true;
+                        break;
+                    }
+                    Err(e) => error = Some(format!("Failed to list object versions: {}", e)),
+                }
+            }
+        } else {
+            let mut pager =
+                service.pager(bucket_name, None, None, opts.common.bucket_region.clone());
+
+            while error.is_none() && !job.is_cancelled() {
+                match pager.next_page().await {
+                    Ok(Some(objects)) => {
+                        let mut failed = Vec::new();
+
+                        if !opts.dry_run {
+                            let keys: Vec<String> =
+                                objects.iter().map(|object| object.key.clone()).collect();
+
+                            match service.delete_keys(bucket_name, &keys).await {
+                                Ok(batch_failed) => failed = batch_failed,
+                                Err(e) => {
+                                    error = Some(format!("Failed to delete objects: {}", e));
+                                    break;
+                                }
+                            }
+                        }
+
+                        let failed_keys: HashSet<&str> =
+                            failed.iter().map(|f| f.key.as_str()).collect();
+
+                        objects
+                            .iter()
+                            .filter(|o| !failed_keys.contains(o.key.as_str()))
+                            .for_each(|o| summary.add_object(o));
+                        errors.extend(failed.iter().map(|f| f.to_string()));
+
+                        jobs::emit(
+                            &app,
+                            EmptyBucketProgress {
+                                job_id: job.id.clone(),
+                                summary: summary.clone(),
+                            },
+                        );
+                    }
+                    Ok(None) => {
+                        completed = true;
+                        break;
+                    }
+                    Err(e) => error = Some(format!("Failed to list objects: {}", e)),
+                }
+            }
+        }
+
+        let mut bucket_deleted = false;
+
+        if !opts.dry_run {
+            if completed && error.is_none() && errors.is_empty() && !job.is_cancelled() {
+                match service.delete_bucket(bucket_name).await {
+                    Ok(()) => bucket_deleted = true,
+                    Err(e) => error = Some(format!("Failed to delete bucket: {}", e)),
+                }
+            }
+
+            if bucket_deleted {
+                forget_bucket(&app, &opts.common, bucket_name).await;
+            } else {
+                invalidate_caches(
+                    &app,
+                    &opts.common,
+                    bucket_name,
+                    &Mutation::subtree(String::new()),
+                )
+                .await;
+            }
+        }
+
+        jobs::emit(
+            &app,
+            EmptyBucketFinished {
+                job_id: job.id.clone(),
+                summary,
+                dry_run: opts.dry_run,
+                bucket_deleted,
+                errors,
+                cancelled: job.is_cancelled(),
+                error,
+            },
+        );
+
+        job.finish().await;
+    });
+
+    Ok(job_id)
 }
 
 #[derive(Serialize, Deserialize, Type)]
-pub struct DownloadFolderOptions {
+pub struct ListObjectsOptions {
     common: CommonOperationOptions,
     bucket_name: String,
-    prefix: String,
+    prefix: Option<String>,
+    // Hierarchy separator, defaults to a slash
+    delimiter: Option<String>,
+    // Lists every key below the prefix without grouping into folders
+    flat: bool,
+}
+
+// The delimiter folders are browsed with, keys ending with it are folder markers
+fn folder_delimiter(delimiter: Option<&str>) -> Result<&str, String> {
+    match delimiter {
+        Some("") => Err("Delimiter must not be empty".to_string()),
+        Some(delimiter) => Ok(delimiter),
+        None => Ok(DEFAULT_DELIMITER),
+    }
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn download_folder(
-    opts: DownloadFolderOptions,
+pub async fn list_objects(
+    opts: ListObjectsOptions,
     state: State<'_, ConnectionMap>,
-) -> Result<Vec<u8>, String> {
-    let service = create_s3_service(&opts.common, state).await?;
+    regions: State<'_, BucketRegionCache>,
+    listing_cache: State<'_, ListingCache>,
+) -> Result<Vec<ObjectInfo>, String> {
+    let delimiter = folder_delimiter(opts.delimiter.as_deref())?;
+    let listing_key = ListingKey {
+        connection_id: opts.common.connection.id.clone(),
+        bucket_name: opts.bucket_name.clone(),
+        prefix: opts.prefix.clone().unwrap_or_default(),
+        delimiter: delimiter.to_string(),
+        flat: opts.flat,
+    };
 
-    service
-        .download_folder(&opts.bucket_name, &opts.prefix, opts.common.bucket_region)
+    if let Some(objects) = listing_cache.get(&listing_key).await {
+        return Ok(objects);
+    }
+
+    let generation = listing_cache.generation().await;
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    // A flat listing still marks keys ending with the delimiter as folders
+    let mut pager = service
+        .pager(
+            &opts.bucket_name,
+            opts.prefix.as_deref(),
+            (!opts.flat).then_some(delimiter),
+            opts.common.bucket_region.clone(),
+        )
+        .folder_delimiter(delimiter);
+    let mut objects = Vec::new();
+
+    while let Some(page) = pager
+        .next_page()
         .await
-        .map_err(|e| format!("Failed to download folder: {}", e))
-}
+        .map_err(|e| format!("Failed to list objects: {}", e))?
+    {
+        objects.extend(page);
+    }
 
-#[derive(Serialize, Deserialize, Type)]
-pub struct UploadObjectsOptions {
-    common: CommonOperationOptions,
-    bucket_name: String,
-    prefix: Option<String>,
-    file_paths: Vec<PathBuf>,
+    listing_cache
+        .insert(listing_key, objects.clone(), generation)
+        .await;
+
+    Ok(objects)
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn upload_objects(
-    opts: UploadObjectsOptions,
-    state: State<'_, ConnectionMap>,
+pub async fn set_listing_cache_ttl(
+    ttl_seconds: u64,
+    listing_cache: State<'_, ListingCache>,
 ) -> Result<(), String> {
-    let service = create_s3_service(&opts.common, state).await?;
+    listing_cache
+        .set_ttl(Duration::from_secs(ttl_seconds))
+        .await;
 
-    service
-        .upload_objects(&opts.bucket_name, opts.prefix, opts.file_paths)
-        .await
-        .map_err(|e| format!("Failed to download objects: {}", e))
+    Ok(())
 }
 
 #[derive(Serialize, Deserialize, Type)]
-pub struct CreateFolderOptions {
-    common: CommonOperationOptions,
-    bucket_name: String,
-    folder_key: String,
+pub struct ClearListingCacheOptions {
+    connection: Connection,
+    bucket_name: Option<String>,
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn create_folder(
-    opts: CreateFolderOptions,
-    state: State<'_, ConnectionMap>,
+pub async fn clear_listing_cache(
+    opts: ClearListingCacheOptions,
+    listing_cache: State<'_, ListingCache>,
 ) -> Result<(), String> {
-    let service = create_s3_service(&opts.common, state).await?;
+    listing_cache
+        .clear(&opts.connection.id, opts.bucket_name.as_deref())
+        .await;
 
-    service
-        .create_folder(&opts.bucket_name, &opts.folder_key)
-        .await
-        .map_err(|e| format!("Failed to create folder: {}", e))
+    Ok(())
 }
 
 #[derive(Serialize, Deserialize, Type)]
-pub struct DeleteFolderOptions {
+pub struct ListObjectsJobOptions {
     common: CommonOperationOptions,
     bucket_name: String,
-    prefix: String,
+    prefix: Option<String>,
+    recursive: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct ListObjectsBatch {
+    job_id: String,
+    objects: Vec<ObjectInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct ListObjectsFinished {
+    job_id: String,
+    count: u64,
+    cancelled: bool,
+    error: Option<String>,
 }
 
+// Starts a background listing and returns its job id right away. Entries are
+// delivered page by page through `ListObjectsBatch` events, followed by a
+// single `ListObjectsFinished` once the walk ends, fails or is cancelled.
 #[tauri::command]
 #[specta::specta]
-pub async fn delete_folder(
-    opts: DeleteFolderOptions,
+pub async fn list_objects_job(
+    app: AppHandle<tauri::Wry>,
+    opts: ListObjectsJobOptions,
     state: State<'_, ConnectionMap>,
-) -> Result<(), String> {
-    let service = create_s3_service(&opts.common, state).await?;
+    regions: State<'_, BucketRegionCache>,
+    job_map: State<'_, JobMap>,
+) -> Result<String, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let job = JobHandle::register(job_map.inner()).await;
+    let job_id = job.id.clone();
 
-    service
-        .delete_folder(&opts.bucket_name, &opts.prefix, opts.common.bucket_region)
-        .await
-        .map_err(|e| format!("Failed to delete folder: {}", e))
+    tauri::async_runtime::spawn(async move {
+        let mut pager = service.pager(
+            &opts.bucket_name,
+            opts.prefix.as_deref(),
+            (!opts.recursive).then_some(DEFAULT_DELIMITER),
+            opts.common.bucket_region,
+        );
+        let mut count = 0;
+        let mut error = None;
+
+        while !job.is_cancelled() {
+            match pager.next_page().await {
+                Ok(Some(objects)) => {
+                    count += objects.len() as u64;
+                    jobs::emit(
+                        &app,
+                        ListObjectsBatch {
+                            job_id: job.id.clone(),
+                            objects,
+                        },
+                    );
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    error = Some(format!("Failed to list objects: {}", e));
+                    break;
+                }
+            }
+        }
+
+        jobs::emit(
+            &app,
+            ListObjectsFinished {
+                job_id: job.id.clone(),
+                count,
+                cancelled: job.is_cancelled(),
+                error,
+            },
+        );
+
+        job.finish().await;
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_job(job_id: String, job_map: State<'_, JobMap>) -> Result<(), String> {
+    jobs::cancel(job_map.inner(), &job_id).await
 }
 
 #[derive(Serialize, Deserialize, Type)]
-pub struct MoveObjectsOptions {
+pub struct SearchObjectsOptions {
     common: CommonOperationOptions,
     bucket_name: String,
-    keys: Vec<String>,
-    destination_prefix: String,
+    prefix: Option<String>,
+    filters: SearchFilters,
+    max_scanned: Option<u64>,
 }
 
-#[tauri::command]
-#[specta::specta]
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct SearchObjectsMatches {
+    job_id: String,
+    objects: Vec<ObjectInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct SearchObjectsFinished {
+    job_id: String,
+    scanned: u64,
+    matched: u64,
+    truncated: bool,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn search_objects(
+    app: AppHandle<tauri::Wry>,
+    opts: SearchObjectsOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+    job_map: State<'_, JobMap>,
+) -> Result<String, String> {
+    let matcher = SearchMatcher::new(opts.prefix.as_deref(), &opts.filters)?;
+    let max_scanned = opts.max_scanned.unwrap_or(search::DEFAULT_MAX_SCANNED);
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let job = JobHandle::register(job_map.inner()).await;
+    let job_id = job.id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let mut pager = service.pager(
+            &opts.bucket_name,
+            opts.prefix.as_deref(),
+            None,
+            opts.common.bucket_region,
+        );
+        let mut scanned = 0;
+        let mut matched = 0;
+        let mut truncated = false;
+        let mut error = None;
+
+        while !job.is_cancelled() && !truncated {
+            let objects = match pager.next_page().await {
+                Ok(Some(objects)) => objects,
+                Ok(None) => break,
+                Err(e) => {
+                    error = Some(format!("Failed to search objects: {}", e));
+                    break;
+                }
+            };
+
+            let mut matches = Vec::new();
+
+            for object in objects {
+                if scanned >= max_scanned {
+                    truncated = true;
+                    break;
+                }
+
+                scanned += 1;
+
+                if matcher.matches(&object) {
+                    matches.push(object);
+                }
+            }
+
+            if !matches.is_empty() {
+                matched += matches.len() as u64;
+                jobs::emit(
+                    &app,
+                    SearchObjectsMatches {
+                        job_id: job.id.clone(),
+                        objects: matches,
+                    },
+                );
+            }
+        }
+
+        jobs::emit(
+            &app,
+            SearchObjectsFinished {
+                job_id: job.id.clone(),
+                scanned,
+                matched,
+                truncated,
+                cancelled: job.is_cancelled(),
+                error,
+            },
+        );
+
+        job.finish().await;
+    });
+
+    Ok(job_id)
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct FolderSizeOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: Option<String>,
+    force_refresh: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct FolderSizeProgress {
+    job_id: String,
+    prefix: String,
+    total_size: i64,
+    object_count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct FolderSizeFinished {
+    job_id: String,
+    prefix: String,
+    size: Option<FolderSize>,
+    cached: bool,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+// Sizes are cached per connection, bucket and prefix until one of our own
+// mutations touches that prefix. A cache hit still goes through a job so the
+// frontend only has to handle one flow.
+#[tauri::command]
+#[specta::specta]
+pub async fn calculate_folder_size(
+    app: AppHandle<tauri::Wry>,
+    opts: FolderSizeOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+    job_map: State<'_, JobMap>,
+    usage_cache: State<'_, FolderSizeCache>,
+) -> Result<String, String> {
+    let prefix = usage::normalize_prefix(opts.prefix.as_deref());
+    let connection_id = opts.common.connection.id.clone();
+    let usage_cache = usage_cache.inner().clone();
+    let generation = usage_cache.generation().await;
+
+    let cached = if opts.force_refresh {
+        None
+    } else {
+        usage_cache
+            .get(&connection_id, &opts.bucket_name, &prefix)
+            .await
+    };
+
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let job = JobHandle::register(job_map.inner()).await;
+    let job_id = job.id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        if let Some(size) = cached {
+            jobs::emit(
+                &app,
+                FolderSizeFinished {
+                    job_id: job.id.clone(),
+                    prefix,
+                    size: Some(size),
+                    cached: true,
+                    cancelled: false,
+                    error: None,
+                },
+            );
+
+            job.finish().await;
+            return;
+        }
+
+        let mut pager = service.pager(
+            &opts.bucket_name,
+            Some(prefix.as_str()),
+            None,
+            opts.common.bucket_region,
+        );
+        let mut totals = UsageTotals::default();
+        let mut error = None;
+        let mut completed = false;
+
+        while !job.is_cancelled() {
+            match pager.next_page().await {
+                Ok(Some(objects)) => {
+                    for object in &objects {
+                        totals.add(object);
+                    }
+
+                    jobs::emit(
+                        &app,
+                        FolderSizeProgress {
+                            job_id: job.id.clone(),
+                            prefix: prefix.clone(),
+                            total_size: totals.total_size,
+                            object_count: totals.object_count,
+                        },
+                    );
+                }
+                Ok(None) => {
+                    completed = true;
+                    break;
+                }
+                Err(e) => {
+                    error = Some(format!("Failed to calculate folder size: {}", e));
+                    break;
+                }
+            }
+        }
+
+        let size = if completed {
+            let size = FolderSize {
+                prefix: prefix.clone(),
+                total_size: totals.total_size,
+                object_count: totals.object_count,
+                storage_classes: totals.storage_classes(),
+                calculated_at: DateTime::from(SystemTime::now()).to_string(),
+            };
+
+            usage_cache
+                .insert(&connection_id, &opts.bucket_name, size.clone(), generation)
+                .await;
+
+            Some(size)
+        } else {
+            None
+        };
+
+        jobs::emit(
+            &app,
+            FolderSizeFinished {
+                job_id: job.id.clone(),
+                prefix,
+                size,
+                cached: false,
+                cancelled: job.is_cancelled(),
+                error,
+            },
+        );
+
+        job.finish().await;
+    });
+
+    Ok(job_id)
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct BucketReportOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: Option<String>,
+    top_n: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct BucketReportProgress {
+    job_id: String,
+    total_size: i64,
+    object_count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct BucketReportFinished {
+    job_id: String,
+    report: Option<BucketReport>,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn generate_bucket_report(
+    app: AppHandle<tauri::Wry>,
+    opts: BucketReportOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+    job_map: State<'_, JobMap>,
+) -> Result<String, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let job = JobHandle::register(job_map.inner()).await;
+    let job_id = job.id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let prefix = usage::normalize_prefix(opts.prefix.as_deref());
+        let mut pager = service.pager(
+            &opts.bucket_name,
+            Some(prefix.as_str()),
+            None,
+            opts.common.bucket_region,
+        );
+        let mut builder = ReportBuilder::new(
+            prefix.clone(),
+            opts.top_n.unwrap_or(report::DEFAULT_TOP_N),
+            DateTime::from(SystemTime::now()),
+        );
+        let mut error = None;
+        let mut completed = false;
+
+        while !job.is_cancelled() {
+            match pager.next_page().await {
+                Ok(Some(objects)) => {
+                    for object in &objects {
+                        builder.add(object);
+                    }
+
+                    jobs::emit(
+                        &app,
+                        BucketReportProgress {
+                            job_id: job.id.clone(),
+                            total_size: builder.totals().total_size,
+                            object_count: builder.totals().object_count,
+                        },
+                    );
+                }
+                Ok(None) => {
+                    completed = true;
+                    break;
+                }
+                Err(e) => {
+                    error = Some(format!("Failed to generate bucket report: {}", e));
+                    break;
+                }
+            }
+        }
+
+        jobs::emit(
+            &app,
+            BucketReportFinished {
+                job_id: job.id.clone(),
+                report: completed.then(|| builder.finish(opts.bucket_name)),
+                cancelled: job.is_cancelled(),
+                error,
+            },
+        );
+
+        job.finish().await;
+    });
+
+    Ok(job_id)
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct ExportBucketReportOptions {
+    report: BucketReport,
+    format: ReportFormat,
+    file_path: PathBuf,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn export_bucket_report(opts: ExportBucketReportOptions) -> Result<(), String> {
+    let contents = match opts.format {
+        ReportFormat::Json => serde_json::to_vec_pretty(&opts.report).map_err(|e| e.to_string()),
+        ReportFormat::Csv => report::report_to_csv(&opts.report).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("Failed to serialize report: {}", e))?;
+
+    tokio::fs::write(&opts.file_path, contents)
+        .await
+        .map_err(|e| format!("Failed to write report: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct FindDuplicatesOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: Option<String>,
+    // Download and hash every candidate instead of trusting ETags
+    verify: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct DuplicateScanProgress {
+    job_id: String,
+    scanned_objects: u64,
+    hashed_objects: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct DuplicateScanFinished {
+    job_id: String,
+    report: Option<DuplicateReport>,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+// Objects are grouped by size first, only same-sized objects are compared by
+// ETag. With `verify` the candidates are downloaded and compared by SHA-256,
+// which also catches copies whose multipart ETags differ.
+#[tauri::command]
+#[specta::specta]
+pub async fn find_duplicates(
+    app: AppHandle<tauri::Wry>,
+    opts: FindDuplicatesOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+    job_map: State<'_, JobMap>,
+) -> Result<String, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let job = JobHandle::register(job_map.inner()).await;
+    let job_id = job.id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let prefix = usage::normalize_prefix(opts.prefix.as_deref());
+        let mut pager = service.pager(
+            &opts.bucket_name,
+            Some(prefix.as_str()),
+            None,
+            opts.common.bucket_region,
+        );
+        let mut finder = DuplicateFinder::default();
+        let mut error = None;
+        let mut listed = false;
+
+        while !job.is_cancelled() {
+            match pager.next_page().await {
+                Ok(Some(objects)) => {
+                    for object in &objects {
+                        finder.add(object);
+                    }
+
+                    jobs::emit(
+                        &app,
+                        DuplicateScanProgress {
+                            job_id: job.id.clone(),
+                            scanned_objects: finder.scanned(),
+                            hashed_objects: 0,
+                        },
+                    );
+                }
+                Ok(None) => {
+                    listed = true;
+                    break;
+                }
+                Err(e) => {
+                    error = Some(format!("Failed to list objects: {}", e));
+                    break;
+                }
+            }
+        }
+
+        let mut groups = None;
+
+        if listed && !opts.verify {
+            groups = Some(finder.etag_groups());
+        } else if listed {
+            let mut verified_groups = Vec::new();
+            let mut hashed_objects = 0;
+
+            'sets: for (size, keys) in finder.verification_sets() {
+                let mut hashes = Vec::with_capacity(keys.len());
+
+                for key in keys {
+                    if job.is_cancelled() {
+                        break 'sets;
+                    }
+
+                    match service.sha256_object(&opts.bucket_name, &key).await {
+                        Ok(hash) => hashes.push((key, hash)),
+                        Err(e) => {
+                            error = Some(format!("Failed to hash {}: {}", key, e));
+                            break 'sets;
+                        }
+                    }
+
+                    hashed_objects += 1;
+
+                    jobs::emit(
+                        &app,
+                        DuplicateScanProgress {
+                            job_id: job.id.clone(),
+                            scanned_objects: finder.scanned(),
+                            hashed_objects,
+                        },
+                    );
+                }
+
+                verified_groups.extend(duplicates::hash_groups(size, hashes));
+            }
+
+            if error.is_none() && !job.is_cancelled() {
+                groups = Some(verified_groups);
+            }
+        }
+
+        let report = groups.map(|groups| {
+            duplicates::build_report(
+                opts.bucket_name,
+                prefix,
+                finder.scanned(),
+                opts.verify,
+                groups,
+            )
+        });
+
+        jobs::emit(
+            &app,
+            DuplicateScanFinished {
+                job_id: job.id.clone(),
+                report,
+                cancelled: job.is_cancelled(),
+                error,
+            },
+        );
+
+        job.finish().await;
+    });
+
+    Ok(job_id)
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct DeleteDuplicatesOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    selections: Vec<DuplicateSelection>,
+}
+
+// Deletes every key of a group except the one to keep. Returns the deleted keys.
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_duplicates(
+    app: AppHandle<tauri::Wry>,
+    opts: DeleteDuplicatesOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Vec<String>, String> {
+    let keys = duplicates::keys_to_delete(&opts.selections)?;
+
+    if keys.is_empty() {
+        return Ok(keys);
+    }
+
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    let result = service
+        .delete_objects(&opts.bucket_name, keys.clone())
+        .await
+        .map_err(|e| format!("Failed to delete duplicates: {}", e));
+
+    let mutation = Mutation::keys(keys.clone());
+    invalidate_caches(&app, &opts.common, &opts.bucket_name, &mutation).await;
+
+    result.map(|_| keys)
+}
+
+// A bucket and prefix on any saved connection
+#[derive(Serialize, Deserialize, Type)]
+pub struct ObjectLocation {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct CompareLocationsOptions {
+    left: ObjectLocation,
+    right: ObjectLocation,
+    // Hash both copies when sizes match but the ETags can't be compared
+    verify: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct CompareProgress {
+    job_id: String,
+    left_count: u64,
+    right_count: u64,
+    hashed_objects: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct CompareFinished {
+    job_id: String,
+    report: Option<CompareReport>,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn compare_locations(
+    app: AppHandle<tauri::Wry>,
+    opts: CompareLocationsOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+    job_map: State<'_, JobMap>,
+) -> Result<String, String> {
+    let left_service = create_bucket_service(
+        &opts.left.common,
+        &opts.left.bucket_name,
+        state.clone(),
+        regions.clone(),
+    )
+    .await?;
+    let right_service =
+        create_bucket_service(&opts.right.common, &opts.right.bucket_name, state, regions).await?;
+    let job = JobHandle::register(job_map.inner()).await;
+    let job_id = job.id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let left_prefix = usage::normalize_prefix(opts.left.prefix.as_deref());
+        let right_prefix = usage::normalize_prefix(opts.right.prefix.as_deref());
+        let mut left_pager = left_service.pager(
+            &opts.left.bucket_name,
+            Some(left_prefix.as_str()),
+            None,
+            opts.left.common.bucket_region,
+        );
+        let mut right_pager = right_service.pager(
+            &opts.right.bucket_name,
+            Some(right_prefix.as_str()),
+            None,
+            opts.right.common.bucket_region,
+        );
+        let mut comparer = ListingComparer::new(
+            CompareReport {
+                left_bucket: opts.left.bucket_name.clone(),
+                left_prefix: left_prefix.clone(),
+                right_bucket: opts.right.bucket_name.clone(),
+                right_prefix: right_prefix.clone(),
+                ..Default::default()
+            },
+            opts.verify,
+        );
+        let mut hashed_objects = 0;
+        let mut error = None;
+
+        // Whichever side ran out of buffered keys is listed next
+        while !job.is_cancelled() && !comparer.is_done() {
+            let listed = if comparer.needs_left() {
+                left_pager
+                    .next_page()
+                    .await
+                    .map(|page| comparer.push_left(page))
+            } else {
+                right_pager
+                    .next_page()
+                    .await
+                    .map(|page| comparer.push_right(page))
+            };
+
+            if let Err(e) = listed {
+                error = Some(format!("Failed to list objects: {}", e));
+                break;
+            }
+
+            jobs::emit(
+                &app,
+                CompareProgress {
+                    job_id: job.id.clone(),
+                    left_count: comparer.report.left_count,
+                    right_count: comparer.report.right_count,
+                    hashed_objects,
+                },
+            );
+        }
+
+        if error.is_none() {
+            for pending in comparer.take_pending() {
+                if job.is_cancelled() {
+                    break;
+                }
+
+                let left_key = format!("{}{}", left_prefix, pending.key);
+                let right_key = format!("{}{}", right_prefix, pending.key);
+
+                match tokio::try_join!(
+                    left_service.sha256_object(&opts.left.bucket_name, &left_key),
+                    right_service.sha256_object(&opts.right.bucket_name, &right_key),
+                ) {
+                    Ok((left_hash, right_hash)) => {
+                        comparer.add_checksum_result(pending, left_hash == right_hash)
+                    }
+                    Err(e) => {
+                        error = Some(format!("Failed to hash {}: {}", pending.key, e));
+                        break;
+                    }
+                }
+
+                hashed_objects += 1;
+
+                jobs::emit(
+                    &app,
+                    CompareProgress {
+                        job_id: job.id.clone(),
+                        left_count: comparer.report.left_count,
+                        right_count: comparer.report.right_count,
+                        hashed_objects,
+                    },
+                );
+            }
+        }
+
+        let cancelled = job.is_cancelled();
+
+        jobs::emit(
+            &app,
+            CompareFinished {
+                job_id: job.id.clone(),
+                report: (error.is_none() && !cancelled).then(|| comparer.finish()),
+                cancelled,
+                error,
+            },
+        );
+
+        job.finish().await;
+    });
+
+    Ok(job_id)
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct ExportCompareReportOptions {
+    report: CompareReport,
+    format: ReportFormat,
+    file_path: PathBuf,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn export_compare_report(opts: ExportCompareReportOptions) -> Result<(), String> {
+    let contents = match opts.format {
+        ReportFormat::Json => serde_json::to_vec_pretty(&opts.report).map_err(|e| e.to_string()),
+        ReportFormat::Csv => compare::compare_to_csv(&opts.report).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("Failed to serialize report: {}", e))?;
+
+    tokio::fs::write(&opts.file_path, contents)
+        .await
+        .map_err(|e| format!("Failed to write report: {}", e))
+}
+
+const COPY_CONCURRENCY: usize = 8;
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct CopyObjectsOptions {
+    source: ObjectLocation,
+    destination: ObjectLocation,
+    // Relative to the source prefix, e.g. the keys of a compare report
+    keys: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct CopyObjectsProgress {
+    job_id: String,
+    copied: u64,
+    failed: u64,
+    total: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct CopyObjectsFinished {
+    job_id: String,
+    copied: u64,
+    errors: Vec<String>,
+    cancelled: bool,
+}
+
+// Copies keys from one location to another, across buckets and connections.
+// Objects keep their path relative to the prefix.
+#[tauri::command]
+#[specta::specta]
+pub async fn copy_objects(
+    app: AppHandle<tauri::Wry>,
+    opts: CopyObjectsOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+    job_map: State<'_, JobMap>,
+) -> Result<String, String> {
+    let source_service = create_bucket_service(
+        &opts.source.common,
+        &opts.source.bucket_name,
+        state.clone(),
+        regions.clone(),
+    )
+    .await?;
+    let destination_service = create_bucket_service(
+        &opts.destination.common,
+        &opts.destination.bucket_name,
+        state,
+        regions,
+    )
+    .await?;
+    let same_connection = opts.source.common.connection.id == opts.destination.common.connection.id;
+    let job = JobHandle::register(job_map.inner()).await;
+    let job_id = job.id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let source_prefix = usage::normalize_prefix(opts.source.prefix.as_deref());
+        let destination_prefix = usage::normalize_prefix(opts.destination.prefix.as_deref());
+        let total = opts.keys.len() as u64;
+        let mut keys = opts.keys.into_iter();
+        let mut tasks = tokio::task::JoinSet::new();
+        let mut copied = Vec::new();
+        let mut errors = Vec::new();
+
+        loop {
+            while tasks.len() < COPY_CONCURRENCY && !job.is_cancelled() {
+                let Some(key) = keys.next() else {
+                    break;
+                };
+
+                let source_service = source_service.clone();
+                let destination_service = destination_service.clone();
+                let source_bucket = opts.source.bucket_name.clone();
+                let destination_bucket = opts.destination.bucket_name.clone();
+                let source_key = format!("{}{}", source_prefix, key);
+                let destination_key = format!("{}{}", destination_prefix, key);
+
+                tasks.spawn(async move {
+                    let result = source_service
+                        .copy_object_to(
+                            &source_bucket,
+                            &source_key,
+                            &destination_service,
+                            &destination_bucket,
+                            &destination_key,
+                            same_connection,
+                        )
+                        .await
+                        .map_err(|e| format!("Failed to copy {}: {}", source_key, e));
+
+                    result.map(|_| destination_key)
+                });
+            }
+
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+
+            match joined {
+                Ok(Ok(destination_key)) => copied.push(destination_key),
+                Ok(Err(e)) => errors.push(e),
+                Err(e) => errors.push(format!("Task panicked: {}", e)),
+            }
+
+            jobs::emit(
+                &app,
+                CopyObjectsProgress {
+                    job_id: job.id.clone(),
+                    copied: copied.len() as u64,
+                    failed: errors.len() as u64,
+                    total,
+                },
+            );
+        }
+
+        let copied_count = copied.len() as u64;
+        let mutation = Mutation::keys(copied);
+        invalidate_caches(
+            &app,
+            &opts.destination.common,
+            &opts.destination.bucket_name,
+            &mutation,
+        )
+        .await;
+
+        jobs::emit(
+            &app,
+            CopyObjectsFinished {
+                job_id: job.id.clone(),
+                copied: copied_count,
+                errors,
+                cancelled: job.is_cancelled(),
+            },
+        );
+
+        job.finish().await;
+    });
+
+    Ok(job_id)
+}
+
+const DEFAULT_INVENTORY_PAGE_SIZE: u32 = 1000;
+
+// SQLite calls block, so they run on the blocking thread pool
+async fn with_index<T, E>(
+    index: &ObjectIndex,
+    f: impl FnOnce(&ObjectIndex) -> Result<T, E> + Send + 'static,
+) -> Result<T, String>
+where
+    T: Send + 'static,
+    E: std::fmt::Display + Send + 'static,
+{
+    let index = index.clone();
+
+    tokio::task::spawn_blocking(move || f(&index).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| format!("Index task panicked: {}", e))?
+}
+
+async fn find_inventory(
+    index: &ObjectIndex,
+    inventory_id: i64,
+) -> Result<(InventoryInfo, String), String> {
+    with_index(index, move |index| index.inventory(inventory_id))
+        .await
+        .map_err(|e| format!("Failed to read inventory: {}", e))?
+        .ok_or_else(|| "Inventory not found".to_string())
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct ImportInventoryOptions {
+    common: CommonOperationOptions,
+    // Bucket holding the manifest, usually the inventory destination bucket
+    bucket_name: String,
+    manifest_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct InventoryImportProgress {
+    job_id: String,
+    files_done: u64,
+    files_total: u64,
+    object_count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct InventoryImportFinished {
+    job_id: String,
+    inventory: Option<InventoryInfo>,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+// Loads an S3 Inventory (CSV or Parquet) into the local index so huge buckets
+// can be browsed without listing them. Data files are downloaded to a
+// temporary file one at a time and imported in a transaction each.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_inventory(
+    app: AppHandle<tauri::Wry>,
+    opts: ImportInventoryOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+    job_map: State<'_, JobMap>,
+    index: State<'_, ObjectIndex>,
+) -> Result<String, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let manifest = service
+        .download_object(&opts.bucket_name, &opts.manifest_key, None)
+        .await
+        .map_err(|e| format!("Failed to download inventory manifest: {}", e))?;
+    let manifest = Arc::new(InventoryManifest::parse(&manifest)?);
+
+    // Fail before starting the job when the data can't be read anyway
+    manifest.format()?;
+
+    let inventory = NewInventory {
+        connection_id: opts.common.connection.identity.clone(),
+        bucket_name: manifest.source_bucket.clone(),
+        url_base: service.url_base(&manifest.source_bucket, opts.common.bucket_region.clone()),
+        manifest_bucket: opts.bucket_name.clone(),
+        manifest_key: opts.manifest_key.clone(),
+        inventory_date: manifest.inventory_date()?.to_string(),
+        imported_at: DateTime::from(SystemTime::now()).to_string(),
+    };
+    let index = index.inner().clone();
+    let job = JobHandle::register(job_map.inner()).await;
+    let job_id = job.id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let files_total = manifest.files.len() as u64;
+        let mut object_count = 0;
+        let mut error = None;
+
+        let inventory_id =
+            match with_index(&index, move |index| index.create_inventory(&inventory)).await {
+                Ok(inventory_id) => Some(inventory_id),
+                Err(e) => {
+                    error = Some(format!("Failed to create inventory: {}", e));
+                    None
+                }
+            };
+
+        if let Some(inventory_id) = inventory_id {
+            for (files_done, file) in manifest.files.iter().enumerate() {
+                if job.is_cancelled() {
+                    break;
+                }
+
+                let path = std::env::temp_dir().join(format!("inventory-{}", Uuid::new_v4()));
+
+                let imported = match service
+                    .download_object_to_file(&opts.bucket_name, &file.key, &path)
+                    .await
+                {
+                    Ok(()) => {
+                        let manifest = manifest.clone();
+                        let path = path.clone();
+
+                        with_index(&index, move |index| {
+                            index.import_inventory_file(inventory_id, &manifest, &path)
+                        })
+                        .await
+                    }
+                    Err(e) => Err(e.to_string()),
+                };
+
+                let _ = tokio::fs::remove_file(&path).await;
+
+                match imported {
+                    Ok(count) => object_count += count,
+                    Err(e) => {
+                        error = Some(format!("Failed to import {}: {}", file.key, e));
+                        break;
+                    }
+                }
+
+                jobs::emit(
+                    &app,
+                    InventoryImportProgress {
+                        job_id: job.id.clone(),
+                        files_done: files_done as u64 + 1,
+                        files_total,
+                        object_count,
+                    },
+                );
+            }
+        }
+
+        let mut imported = None;
+
+        if let Some(inventory_id) = inventory_id {
+            if error.is_none() && !job.is_cancelled() {
+                let completed = with_index(&index, move |index| {
+                    index.complete_inventory(inventory_id)?;
+                    index.inventory(inventory_id)
+                })
+                .await;
+
+                match completed {
+                    Ok(inventory) => imported = inventory.map(|(inventory, _)| inventory),
+                    Err(e) => error = Some(format!("Failed to complete inventory: {}", e)),
+                }
+            } else {
+                // Partial imports would silently miss objects
+                let _ = with_index(&index, move |index| index.delete_inventory(inventory_id)).await;
+            }
+        }
+
+        jobs::emit(
+            &app,
+            InventoryImportFinished {
+                job_id: job.id.clone(),
+                inventory: imported,
+                cancelled: job.is_cancelled(),
+                error,
+            },
+        );
+
+        job.finish().await;
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_inventories(index: State<'_, ObjectIndex>) -> Result<Vec<InventoryInfo>, String> {
+    with_index(index.inner(), |index| index.list_inventories())
+        .await
+        .map_err(|e| format!("Failed to list inventories: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_inventory(
+    inventory_id: i64,
+    index: State<'_, ObjectIndex>,
+) -> Result<(), String> {
+    with_index(index.inner(), move |index| {
+        index.delete_inventory(inventory_id)
+    })
+    .await
+    .map_err(|e| format!("Failed to delete inventory: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct ListInventoryObjectsOptions {
+    inventory_id: i64,
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    start_after: Option<String>,
+    max_keys: Option<u32>,
+}
+
+// Browses an imported inventory one folder level at a time, like list_objects
+#[tauri::command]
+#[specta::specta]
+pub async fn list_inventory_objects(
+    opts: ListInventoryObjectsOptions,
+    index: State<'_, ObjectIndex>,
+) -> Result<InventoryListing, String> {
+    let (inventory, url_base) = find_inventory(index.inner(), opts.inventory_id).await?;
+    let prefix = usage::normalize_prefix(opts.prefix.as_deref());
+    let delimiter = folder_delimiter(opts.delimiter.as_deref())?.to_string();
+    let limit = opts.max_keys.unwrap_or(DEFAULT_INVENTORY_PAGE_SIZE) as usize;
+
+    let (objects, next_start_after) = with_index(index.inner(), move |index| {
+        index.browse_inventory(
+            opts.inventory_id,
+            &url_base,
+            &prefix,
+            &delimiter,
+            opts.start_after.as_deref(),
+            limit,
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to list inventory objects: {}", e))?;
+
+    Ok(InventoryListing {
+        inventory,
+        objects,
+        next_start_after,
+    })
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct SearchInventoryOptions {
+    inventory_id: i64,
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    filters: SearchFilters,
+    max_results: Option<u32>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn search_inventory(
+    opts: SearchInventoryOptions,
+    index: State<'_, ObjectIndex>,
+) -> Result<InventorySearchResult, String> {
+    let (inventory, url_base) = find_inventory(index.inner(), opts.inventory_id).await?;
+    let prefix = usage::normalize_prefix(opts.prefix.as_deref());
+    let delimiter = folder_delimiter(opts.delimiter.as_deref())?.to_string();
+    let matcher = SearchMatcher::new(Some(prefix.as_str()), &opts.filters)?;
+    let max_results = opts.max_results.unwrap_or(DEFAULT_INVENTORY_PAGE_SIZE) as usize;
+
+    let (objects, truncated) = with_index(index.inner(), move |index| {
+        let mut objects = Vec::new();
+        let mut truncated = false;
+
+        index.scan_inventory(
+            opts.inventory_id,
+            &url_base,
+            &prefix,
+            &delimiter,
+            |object| {
+                if !matcher.matches(&object) {
+                    return true;
+                }
+
+                if objects.len() == max_results {
+                    truncated = true;
+                    return false;
+                }
+
+                objects.push(object);
+                true
+            },
+        )?;
+
+        Ok::<_, rusqlite::Error>((objects, truncated))
+    })
+    .await
+    .map_err(|e| format!("Failed to search inventory: {}", e))?;
+
+    Ok(InventorySearchResult {
+        inventory,
+        objects,
+        truncated,
+    })
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct InventoryReportOptions {
+    inventory_id: i64,
+    prefix: Option<String>,
+    top_n: Option<u32>,
+}
+
+// Same report as generate_bucket_report, computed from the inventory. Object
+// ages are relative to the inventory date.
+#[tauri::command]
+#[specta::specta]
+pub async fn generate_inventory_report(
+    opts: InventoryReportOptions,
+    index: State<'_, ObjectIndex>,
+) -> Result<InventoryReport, String> {
+    let (inventory, url_base) = find_inventory(index.inner(), opts.inventory_id).await?;
+    let prefix = usage::normalize_prefix(opts.prefix.as_deref());
+    let as_of = parse_timestamp(&inventory.inventory_date)?;
+    let mut builder = ReportBuilder::new(
+        prefix.clone(),
+        opts.top_n.unwrap_or(report::DEFAULT_TOP_N),
+        as_of,
+    );
+    let bucket_name = inventory.bucket_name.clone();
+
+    let report = with_index(index.inner(), move |index| {
+        index.scan_inventory(
+            opts.inventory_id,
+            &url_base,
+            &prefix,
+            DEFAULT_DELIMITER,
+            |object| {
+                builder.add(&object);
+                true
+            },
+        )?;
+
+        Ok::<_, rusqlite::Error>(builder.finish(bucket_name))
+    })
+    .await
+    .map_err(|e| format!("Failed to generate inventory report: {}", e))?;
+
+    Ok(InventoryReport { inventory, report })
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct ExportListingOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: Option<String>,
+    format: ListingFormat,
+    file_path: PathBuf,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct ListingExportProgress {
+    job_id: String,
+    object_count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct ListingExportFinished {
+    job_id: String,
+    object_count: u64,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+// Writes the recursive listing of a prefix to a local file page by page. A
+// cancelled or failed export removes the partial file.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_listing(
+    app: AppHandle<tauri::Wry>,
+    opts: ExportListingOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+    job_map: State<'_, JobMap>,
+) -> Result<String, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let partial_path = export::partial_path(&opts.file_path);
+    let writer = ListingWriter::create(&partial_path, &opts.format)
+        .map_err(|e| format!("Failed to create export file: {}", e))?;
+    let job = JobHandle::register(job_map.inner()).await;
+    let job_id = job.id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let mut pager = service.pager(
+            &opts.bucket_name,
+            opts.prefix.as_deref(),
+            None,
+            opts.common.bucket_region,
+        );
+        let mut writer = Some(writer);
+        let mut object_count = 0;
+        let mut error = None;
+        let mut completed = false;
+
+        while !job.is_cancelled() {
+            match pager.next_page().await {
+                Ok(Some(objects)) => {
+                    let Some(mut current) = writer.take() else {
+                        break;
+                    };
+                    let page_size = objects.len() as u64;
+
+                    // File writes and Parquet encoding block
+                    let written = tokio::task::spawn_blocking(move || {
+                        let result = current.write(&objects);
+                        (current, result)
+                    })
+                    .await;
+
+                    match written {
+                        Ok((current, Ok(()))) => writer = Some(current),
+                        Ok((_, Err(e))) => {
+                            error = Some(format!("Failed to write listing: {}", e));
+                            break;
+                        }
+                        Err(e) => {
+                            error = Some(format!("Listing writer panicked: {}", e));
+                            break;
+                        }
+                    }
+
+                    object_count += page_size;
+
+                    jobs::emit(
+                        &app,
+                        ListingExportProgress {
+                            job_id: job.id.clone(),
+                            object_count,
+                        },
+                    );
+                }
+                Ok(None) => {
+                    completed = true;
+                    break;
+                }
+                Err(e) => {
+                    error = Some(format!("Failed to list objects: {}", e));
+                    break;
+                }
+            }
+        }
+
+        if let Some(writer) = writer.take().filter(|_| completed) {
+            match tokio::task::spawn_blocking(move || writer.finish()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => error = Some(format!("Failed to write listing: {}", e)),
+                Err(e) => error = Some(format!("Listing writer panicked: {}", e)),
+            }
+        }
+
+        // Closes the partial file before it's renamed or removed
+        drop(writer);
+
+        if completed && error.is_none() {
+            if let Err(e) = tokio::fs::rename(&partial_path, &opts.file_path).await {
+                error = Some(format!("Failed to save export file: {}", e));
+            }
+        }
+
+        if error.is_some() || !completed {
+            let _ = tokio::fs::remove_file(&partial_path).await;
+        }
+
+        jobs::emit(
+            &app,
+            ListingExportFinished {
+                job_id: job.id.clone(),
+                object_count,
+                cancelled: job.is_cancelled(),
+                error,
+            },
+        );
+
+        job.finish().await;
+    });
+
+    Ok(job_id)
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct IndexBucketOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    // Relist the whole bucket instead of only what changed since the last crawl
+    full: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct IndexProgress {
+    job_id: String,
+    bucket_name: String,
+    object_count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Event)]
+pub struct IndexFinished {
+    job_id: String,
+    bucket: Option<IndexedBucket>,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+// Adds a bucket to the local index and crawls it in the background. The first
+// crawl lists everything, later ones only relist the prefixes this app changed
+// since, unless a full crawl is requested to pick up changes made elsewhere.
+#[tauri::command]
+#[specta::specta]
+pub async fn index_bucket(
+    app: AppHandle<tauri::Wry>,
+    opts: IndexBucketOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+    job_map: State<'_, JobMap>,
+    index: State<'_, ObjectIndex>,
+) -> Result<String, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let identity = opts.common.connection.identity.clone();
+    let bucket_name = opts.bucket_name.clone();
+    let url_base = service.url_base(&opts.bucket_name, opts.common.bucket_region.clone());
+    let full = opts.full;
+
+    let (plan, claim) = with_index(index.inner(), move |index| {
+        let bucket_id = index
+            .add_indexed_bucket(&identity, &bucket_name, &url_base)
+            .map_err(|e| e.to_string())?;
+        let claim = index
+            .claim_crawl(bucket_id)
+            .ok_or_else(|| format!("{} is already being indexed", bucket_name))?;
+        let plan = index
+            .start_crawl(bucket_id, full)
+            .map_err(|e| e.to_string())?;
+
+        Ok::<_, String>((plan, claim))
+    })
+    .await
+    .map_err(|e| format!("Failed to start indexing: {}", e))?;
+
+    let index = index.inner().clone();
+    let job = JobHandle::register(job_map.inner()).await;
+    let job_id = job.id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let bucket_id = plan.bucket_id;
+        let generation = plan.generation;
+        let mut remaining = plan.prefixes.into_iter();
+        let mut object_count = 0;
+        let mut error = None;
+        let mut unfinished = Vec::new();
+
+        for prefix in remaining.by_ref() {
+            if job.is_cancelled() {
+                unfinished.push(prefix);
+                break;
+            }
+
+            let mut pager = service.pager(
+                &opts.bucket_name,
+                Some(prefix.as_str()),
+                None,
+                opts.common.bucket_region.clone(),
+            );
+            let mut completed = false;
+
+            while !job.is_cancelled() {
+                match pager.next_page().await {
+                    Ok(Some(objects)) => {
+                        object_count += objects.len() as u64;
+
+                        if let Err(e) = with_index(&index, move |index| {
+                            index.upsert_indexed_objects(bucket_id, generation, &objects)
+                        })
+                        .await
+                        {
+                            error = Some(format!("Failed to write index: {}", e));
+                            break;
+                        }
+
+                        jobs::emit(
+                            &app,
+                            IndexProgress {
+                                job_id: job.id.clone(),
+                                bucket_name: opts.bucket_name.clone(),
+                                object_count,
+                            },
+                        );
+                    }
+                    Ok(None) => {
+                        completed = true;
+                        break;
+                    }
+                    Err(e) => {
+                        error = Some(format!("Failed to list objects: {}", e));
+                        break;
+                    }
+                }
+            }
+
+            // Only a complete listing tells which indexed objects are gone
+            if completed {
+                let stale_prefix = prefix.clone();
+
+                if let Err(e) = with_index(&index, move |index| {
+                    index.remove_stale_objects(bucket_id, generation, &stale_prefix)
+                })
+                .await
+                {
+                    error = Some(format!("Failed to write index: {}", e));
+                }
+            }
+
+            if !completed || error.is_some() {
+                unfinished.push(prefix);
+                break;
+            }
+        }
+
+        unfinished.extend(remaining);
+
+        let indexed_at = DateTime::from(SystemTime::now()).to_string();
+        let finished = with_index(&index, move |index| {
+            index.requeue(bucket_id, &unfinished)?;
+            index.finish_crawl(bucket_id, &indexed_at)?;
+            index.indexed_bucket(bucket_id)
+        })
+        .await;
+
+        let bucket = match finished {
+            Ok(bucket) => bucket,
+            Err(e) => {
+                if error.is_none() {
+                    error = Some(format!("Failed to write index: {}", e));
+                }
+                None
+            }
+        };
+
+        // Released before the finish event, so a crawl started in reaction to it isn't refused
+        drop(claim);
+
+        jobs::emit(
+            &app,
+            IndexFinished {
+                job_id: job.id.clone(),
+                bucket,
+                cancelled: job.is_cancelled(),
+                error,
+            },
+        );
+
+        job.finish().await;
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_indexed_buckets(
+    index: State<'_, ObjectIndex>,
+) -> Result<Vec<IndexedBucket>, String> {
+    with_index(index.inner(), |index| index.list_indexed_buckets())
+        .await
+        .map_err(|e| format!("Failed to list indexed buckets: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct RemoveIndexedBucketOptions {
+    connection_id: String,
+    bucket_name: String,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_indexed_bucket(
+    opts: RemoveIndexedBucketOptions,
+    index: State<'_, ObjectIndex>,
+) -> Result<(), String> {
+    with_index(index.inner(), move |index| {
+        index.remove_indexed_bucket(&opts.connection_id, &opts.bucket_name)
+    })
+    .await
+    .map_err(|e| format!("Failed to remove bucket from index: {}", e))
+}
+
+// Drops indexed buckets that belong to none of the saved or connected
+// connections, e.g. ones indexed with credentials that were removed since.
+// Returns the dropped buckets.
+#[tauri::command]
+#[specta::specta]
+pub async fn prune_indexed_buckets(
+    app: AppHandle<tauri::Wry>,
+    state: State<'_, ConnectionMap>,
+    index: State<'_, ObjectIndex>,
+) -> Result<Vec<IndexedBucket>, String> {
+    let mut identities: Vec<String> = crate::keyring::load_saved_connections(app)
+        .await?
+        .iter()
+        .map(|config| config.identity())
+        .collect();
+    identities.extend(state.lock().await.values().map(|config| config.identity()));
+
+    with_index(index.inner(), move |index| {
+        index.prune_indexed_buckets(&identities)
+    })
+    .await
+    .map_err(|e| format!("Failed to prune index: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct SearchIndexOptions {
+    filters: SearchFilters,
+    delimiter: Option<String>,
+    max_results: Option<u32>,
+}
+
+// Searches every indexed bucket of every connection without touching the network
+#[tauri::command]
+#[specta::specta]
+pub async fn search_index(
+    opts: SearchIndexOptions,
+    index: State<'_, ObjectIndex>,
+) -> Result<IndexSearchResult, String> {
+    let matcher = SearchMatcher::new(None, &opts.filters)?;
+    let delimiter = folder_delimiter(opts.delimiter.as_deref())?.to_string();
+    let max_results = opts.max_results.unwrap_or(DEFAULT_INVENTORY_PAGE_SIZE) as usize;
+
+    with_index(index.inner(), move |index| {
+        let mut matches = Vec::new();
+        let mut truncated = false;
+
+        index.search_indexed_objects(&opts.filters, &delimiter, |found| {
+            if !matcher.matches(&found.object) {
+                return true;
+            }
+
+            if matches.len() == max_results {
+                truncated = true;
+                return false;
+            }
+
+            matches.push(found);
+            true
+        })?;
+
+        Ok::<_, rusqlite::Error>(IndexSearchResult { matches, truncated })
+    })
+    .await
+    .map_err(|e| format!("Failed to search index: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct DownloadObjectOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    key: String,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn download_object(
+    opts: DownloadObjectOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Vec<u8>, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    service
+        .download_object(&opts.bucket_name, &opts.key, None)
+        .await
+        .map_err(|e| format!("Failed to download object: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct ObjectDetailsOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    key: String,
+    version_id: Option<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_object_details(
+    opts: ObjectDetailsOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<ObjectDetails, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    service
+        .get_object_details(&opts.bucket_name, &opts.key, opts.version_id.as_deref())
+        .await
+        .map_err(|e| format!("Failed to get object details: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct PresignGetOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    keys: Vec<String>,
+    expires_in_seconds: u64,
+    overrides: ResponseOverrides,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn presign_get_urls(
+    opts: PresignGetOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Vec<PresignedUrl>, String> {
+    let expires_in = presign_expiry(opts.expires_in_seconds)?;
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let mut urls = Vec::new();
+
+    // Presigning is a local signing operation, no need to run it concurrently
+    for key in &opts.keys {
+        let url = service
+            .presign_get_object(&opts.bucket_name, key, expires_in, &opts.overrides)
+            .await
+            .map_err(|e| format!("Failed to presign URL for {}: {}", key, e))?;
+
+        urls.push(url);
+    }
+
+    Ok(urls)
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct PresignPutOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    key: String,
+    expires_in_seconds: u64,
+    content_type: Option<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn presign_put_url(
+    opts: PresignPutOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<PresignedUpload, String> {
+    let expires_in = presign_expiry(opts.expires_in_seconds)?;
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    service
+        .presign_put_object(&opts.bucket_name, &opts.key, expires_in, opts.content_type)
+        .await
+        .map_err(|e| format!("Failed to presign upload URL: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct PresignPostOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    key_prefix: String,
+    // Policies without a key prefix let the uploader write anywhere in the bucket
+    allow_any_key: bool,
+    min_size: u64,
+    max_size: u64,
+    content_type: Option<String>,
+    expires_in_seconds: u64,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn presign_post_policy(
+    opts: PresignPostOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<PresignedPost, String> {
+    let expires_in = presign_expiry(opts.expires_in_seconds)?;
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    let policy = PostPolicy {
+        bucket_name: &opts.bucket_name,
+        key_prefix: &opts.key_prefix,
+        allow_any_key: opts.allow_any_key,
+        min_size: opts.min_size,
+        max_size: opts.max_size,
+        content_type: opts.content_type.as_deref(),
+    };
+
+    service
+        .presign_post_policy(&policy, expires_in)
+        .map_err(|e| format!("Failed to create POST policy: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct UpdateObjectMetadataOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    target: MetadataTarget,
+    changes: MetadataChanges,
+    dry_run: bool,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_object_metadata(
+    app: AppHandle<tauri::Wry>,
+    opts: UpdateObjectMetadataOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Vec<MetadataUpdate>, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    let keys = match opts.target {
+        MetadataTarget::Object(key) => vec![key],
+        MetadataTarget::Prefix(prefix) => service
+            .list_objects(
+                &opts.bucket_name,
+                Some(&prefix),
+                None,
+                opts.common.bucket_region.clone(),
+            )
+            .await
+            .map_err(|e| format!("Failed to list objects: {}", e))?
+            .into_iter()
+            .filter(|object| !object.is_folder)
+            .map(|object| object.key)
+            .collect(),
+    };
+
+    let dry_run = opts.dry_run;
+    let mut pending = keys.iter().cloned().enumerate();
+    let mut tasks = tokio::task::JoinSet::new();
+    let mut task_keys = HashMap::new();
+    let mut updates: Vec<Option<MetadataUpdate>> = vec![None; keys.len()];
+
+    // A prefix can hold any number of objects, so only a few copies run at once
+    loop {
+        while tasks.len() < COPY_CONCURRENCY {
+            let Some((index, key)) = pending.next() else {
+                break;
+            };
+
+            let service = service.clone();
+            let bucket_name = opts.bucket_name.clone();
+            let changes = opts.changes.clone();
+
+            let handle = tasks.spawn(async move {
+                service
+                    .update_object_metadata(&bucket_name, &key, &changes, dry_run)
+                    .await
+                    .unwrap_or_else(|e| MetadataUpdate::failed(key, e.to_string()))
+            });
+            task_keys.insert(handle.id(), index);
+        }
+
+        let Some(joined) = tasks.join_next_with_id().await else {
+            break;
+        };
+
+        match joined {
+            Ok((id, update)) => updates[task_keys[&id]] = Some(update),
+            Err(e) => {
+                let index = task_keys[&e.id()];
+                updates[index] = Some(MetadataUpdate::failed(
+                    keys[index].clone(),
+                    format!("Task panicked: {}", e),
+                ));
+            }
+        }
+    }
+
+    let updates: Vec<MetadataUpdate> = updates.into_iter().flatten().collect();
+
+    if !dry_run {
+        let mutation = Mutation::keys(keys);
+        invalidate_caches(&app, &opts.common, &opts.bucket_name, &mutation).await;
+    }
+
+    Ok(updates)
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct DownloadObjectsOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    keys: Vec<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn download_objects(
+    opts: DownloadObjectsOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    service
+        .download_objects(&opts.bucket_name, opts.keys)
+        .await
+        .map_err(|e| format!("Failed to download objects: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct DeleteObjectsOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    keys: Vec<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_objects(
+    app: AppHandle<tauri::Wry>,
+    opts: DeleteObjectsOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<(), String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    let result = service
+        .delete_objects(&opts.bucket_name, opts.keys.clone())
+        .await
+        .map_err(|e| format!("Failed to delete objects: {}", e));
+
+    invalidate_caches(
+        &app,
+        &opts.common,
+        &opts.bucket_name,
+        &Mutation::keys(opts.keys),
+    )
+    .await;
+
+    result
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct DownloadFolderOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: String,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn download_folder(
+    opts: DownloadFolderOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Vec<u8>, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    service
+        .download_folder(&opts.bucket_name, &opts.prefix, opts.common.bucket_region)
+        .await
+        .map_err(|e| format!("Failed to download folder: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct UploadObjectsOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: Option<String>,
+    file_paths: Vec<PathBuf>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn upload_objects(
+    app: AppHandle<tauri::Wry>,
+    opts: UploadObjectsOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<(), String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let uploaded_keys = opts
+        .file_paths
+        .iter()
+        .filter_map(|path| upload_key(opts.prefix.as_deref(), path))
+        .collect();
+
+    let result = service
+        .upload_objects(&opts.bucket_name, opts.prefix, opts.file_paths)
+        .await
+        .map_err(|e| format!("Failed to download objects: {}", e));
+
+    invalidate_caches(
+        &app,
+        &opts.common,
+        &opts.bucket_name,
+        &Mutation::keys(uploaded_keys),
+    )
+    .await;
+
+    result
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct CreateFolderOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    folder_key: String,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn create_folder(
+    app: AppHandle<tauri::Wry>,
+    opts: CreateFolderOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<(), String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    let result = service
+        .create_folder(&opts.bucket_name, &opts.folder_key)
+        .await
+        .map_err(|e| format!("Failed to create folder: {}", e));
+
+    invalidate_caches(
+        &app,
+        &opts.common,
+        &opts.bucket_name,
+        &Mutation::keys(vec![opts.folder_key]),
+    )
+    .await;
+
+    result
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct DeleteFolderOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: String,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_folder(
+    app: AppHandle<tauri::Wry>,
+    opts: DeleteFolderOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<(), String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let mutation = Mutation::subtree(usage::normalize_prefix(Some(&opts.prefix)));
+
+    let result = service
+        .delete_folder(
+            &opts.bucket_name,
+            &opts.prefix,
+            opts.common.bucket_region.clone(),
+        )
+        .await
+        .map_err(|e| format!("Failed to delete folder: {}", e));
+
+    invalidate_caches(&app, &opts.common, &opts.bucket_name, &mutation).await;
+
+    result
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct MoveObjectsOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    keys: Vec<String>,
+    destination_prefix: String,
+}
+
+#[tauri::command]
+#[specta::specta]
 pub async fn move_objects(
+    app: AppHandle<tauri::Wry>,
     opts: MoveObjectsOptions,
     state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
 ) -> Result<(), String> {
-    let service = create_s3_service(&opts.common, state).await?;
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let destination_keys = opts
+        .keys
+        .iter()
+        .map(|key| move_destination_key(key, &opts.destination_prefix));
+    let mutation = Mutation::keys(opts.keys.iter().cloned().chain(destination_keys).collect());
 
-    service
+    let result = service
         .move_objects(&opts.bucket_name, opts.keys, &opts.destination_prefix)
         .await
-        .map_err(|e| format!("Failed to move objects: {}", e))
+        .map_err(|e| format!("Failed to move objects: {}", e));
+
+    invalidate_caches(&app, &opts.common, &opts.bucket_name, &mutation).await;
+
+    result
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct ListObjectVersionsOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: Option<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_object_versions(
+    opts: ListObjectVersionsOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Vec<ObjectVersion>, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    service
+        .list_object_versions(&opts.bucket_name, opts.prefix.as_deref())
+        .await
+        .map_err(|e| format!("Failed to list object versions: {}", e))
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct ObjectVersionOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    key: String,
+    version_id: String,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn download_object_version(
+    opts: ObjectVersionOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Vec<u8>, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    service
+        .download_object(&opts.bucket_name, &opts.key, Some(&opts.version_id))
+        .await
+        .map_err(|e| format!("Failed to download object version: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_object_version(
+    app: AppHandle<tauri::Wry>,
+    opts: ObjectVersionOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Option<String>, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+
+    let result = service
+        .restore_object_version(&opts.bucket_name, &opts.key, &opts.version_id)
+        .await
+        .map_err(|e| format!("Failed to restore object version: {}", e));
+
+    let mutation = Mutation::keys(vec![opts.key]);
+    invalidate_caches(&app, &opts.common, &opts.bucket_name, &mutation).await;
+
+    result
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct UndeleteObjectsOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    keys: Vec<String>,
+}
+
+// Returns the keys that actually had a delete marker removed
+#[tauri::command]
+#[specta::specta]
+pub async fn undelete_objects(
+    app: AppHandle<tauri::Wry>,
+    opts: UndeleteObjectsOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Vec<String>, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let mut markers = Vec::new();
+
+    for key in &opts.keys {
+        match service.latest_delete_marker(&opts.bucket_name, key).await {
+            Ok(Some(marker)) => markers.push(marker),
+            Ok(None) => {}
+            Err(e) => return Err(format!("Failed to undelete {}: {}", key, e)),
+        }
+    }
+
+    // Removing the delete marker on top of each version stack brings back
+    // the version below it
+    let result = service
+        .delete_object_versions(&opts.bucket_name, &markers)
+        .await
+        .map_err(|e| format!("Failed to undelete objects: {}", e));
+    let markers = markers.into_iter().map(|marker| marker.key);
+
+    let failed = match result {
+        Ok(failed) => failed,
+        Err(e) => {
+            // Some batches may have gone through before the error
+            let mutation = Mutation::keys(markers.collect());
+            invalidate_caches(&app, &opts.common, &opts.bucket_name, &mutation).await;
+            return Err(e);
+        }
+    };
+
+    let failed_keys: HashSet<&str> = failed.iter().map(|f| f.key.as_str()).collect();
+    let restored: Vec<String> = markers
+        .filter(|key| !failed_keys.contains(key.as_str()))
+        .collect();
+
+    let mutation = Mutation::keys(restored.clone());
+    invalidate_caches(&app, &opts.common, &opts.bucket_name, &mutation).await;
+
+    match failed.first() {
+        Some(failure) => Err(format!("Failed to undelete {}", failure)),
+        None => Ok(restored),
+    }
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct ListObjectsAtOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: Option<String>,
+    timestamp: String,
+}
+
+// Lists the versions that were current at `timestamp`, keys deleted or not
+// yet created at that time are left out.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_objects_at(
+    opts: ListObjectsAtOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Vec<ObjectVersion>, String> {
+    let at = parse_timestamp(&opts.timestamp)?;
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let mut pager = service.version_pager(&opts.bucket_name, opts.prefix.as_deref());
+    let mut point_in_time = PointInTime::new(at);
+    let mut objects = Vec::new();
+
+    while let Some(page) = pager
+        .next_page()
+        .await
+        .map_err(|e| format!("Failed to list object versions: {}", e))?
+    {
+        objects.extend(
+            page.into_iter()
+                .filter_map(|version| point_in_time.push(version))
+                .filter_map(|key_state| key_state.at),
+        );
+    }
+
+    objects.extend(point_in_time.finish().and_then(|key_state| key_state.at));
+
+    Ok(objects)
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct RestorePrefixToTimeOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: String,
+    timestamp: String,
+    dry_run: bool,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_prefix_to_time(
+    app: AppHandle<tauri::Wry>,
+    opts: RestorePrefixToTimeOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<PointInTimeRestore, String> {
+    let at = parse_timestamp(&opts.timestamp)?;
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let mut pager = service.version_pager(&opts.bucket_name, Some(&opts.prefix));
+    let mut point_in_time = PointInTime::new(at);
+    let mut key_states: Vec<KeyState> = Vec::new();
+
+    while let Some(page) = pager
+        .next_page()
+        .await
+        .map_err(|e| format!("Failed to list object versions: {}", e))?
+    {
+        key_states.extend(
+            page.into_iter()
+                .filter_map(|version| point_in_time.push(version)),
+        );
+    }
+
+    key_states.extend(point_in_time.finish());
+
+    let mut summary = PointInTimeRestore {
+        restored: Vec::new(),
+        deleted: Vec::new(),
+        unchanged: 0,
+        errors: Vec::new(),
+    };
+    let mut to_delete = Vec::new();
+
+    for key_state in key_states {
+        let latest_version_id = key_state.latest.as_ref().map(|v| v.version_id.as_str());
+
+        match &key_state.at {
+            Some(version) if latest_version_id == Some(version.version_id.as_str()) => {
+                summary.unchanged += 1;
+            }
+            Some(version) => {
+                let result = if opts.dry_run {
+                    Ok(None)
+                } else {
+                    service
+                        .restore_object_version(
+                            &opts.bucket_name,
+                            &key_state.key,
+                            &version.version_id,
+                        )
+                        .await
+                };
+
+                match result {
+                    Ok(_) => summary.restored.push(key_state.key),
+                    Err(e) => summary.errors.push(format!("{}: {}", key_state.key, e)),
+                }
+            }
+            None if key_state.latest.is_some() => to_delete.push(key_state.key),
+            None => summary.unchanged += 1,
+        }
+    }
+
+    // Keys that didn't exist yet at that time get a delete marker, in batches
+    if opts.dry_run || to_delete.is_empty() {
+        summary.deleted = to_delete;
+    } else {
+        match service.delete_keys(&opts.bucket_name, &to_delete).await {
+            Ok(failed) => {
+                let failed_keys: HashSet<&str> = failed.iter().map(|f| f.key.as_str()).collect();
+                summary.deleted = to_delete
+                    .iter()
+                    .filter(|key| !failed_keys.contains(key.as_str()))
+                    .cloned()
+                    .collect();
+                summary.errors.extend(failed.iter().map(|f| f.to_string()));
+            }
+            Err(e) => {
+                summary
+                    .errors
+                    .push(format!("Failed to delete objects: {}", e));
+                // Some batches may have gone through before the error
+                let mutation = Mutation::keys(to_delete);
+                invalidate_caches(&app, &opts.common, &opts.bucket_name, &mutation).await;
+            }
+        }
+    }
+
+    if !opts.dry_run {
+        let mutation = Mutation::keys(
+            summary
+                .restored
+                .iter()
+                .chain(summary.deleted.iter())
+                .cloned()
+                .collect(),
+        );
+        invalidate_caches(&app, &opts.common, &opts.bucket_name, &mutation).await;
+    }
+
+    Ok(summary)
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct PurgeVersionsOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: String,
+    rules: PurgeRules,
+    dry_run: bool,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn purge_object_versions(
+    opts: PurgeVersionsOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<PurgeSummary, String> {
+    const BATCH_SIZE: usize = 1000;
+
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let mut pager = service.version_pager(&opts.bucket_name, Some(&opts.prefix));
+    let mut planner = PurgePlanner::new(opts.rules, DateTime::from(SystemTime::now()));
+    let mut summary = PurgeSummary::default();
+
+    // Deletions are flushed in full batches while the listing is still running
+    loop {
+        let page = pager
+            .next_page()
+            .await
+            .map_err(|e| format!("Failed to list object versions: {}", e))?;
+        let done = page.is_none();
+
+        match page {
+            Some(versions) => versions.into_iter().for_each(|v| planner.push(v)),
+            None => planner.finish(),
+        }
+
+        if planner.pending_count() >= BATCH_SIZE || done {
+            let pending = planner.take_pending();
+
+            if opts.dry_run || pending.is_empty() {
+                summary.record(&pending);
+            } else {
+                let failed = service
+                    .delete_object_versions(&opts.bucket_name, &pending)
+                    .await
+                    .map_err(|e| format!("Failed to delete object versions: {}", e))?;
+                let failed_versions: HashSet<(&str, &str)> = failed
+                    .iter()
+                    .map(|f| (f.key.as_str(), f.version_id.as_deref().unwrap_or_default()))
+                    .collect();
+                let deleted: Vec<ObjectVersion> = pending
+                    .into_iter()
+                    .filter(|v| !failed_versions.contains(&(v.key.as_str(), v.version_id.as_str())))
+                    .collect();
+
+                summary.record(&deleted);
+                summary.errors.extend(failed.iter().map(|f| f.to_string()));
+            }
+        }
+
+        if done {
+            break;
+        }
+    }
+
+    Ok(summary)
+}
+
+#[derive(Serialize, Deserialize, Type)]
+pub struct CleanupFolderMarkersOptions {
+    common: CommonOperationOptions,
+    bucket_name: String,
+    prefix: Option<String>,
+    dry_run: bool,
+}
+
+// Removes empty folder markers that are redundant because the folder also
+// contains other objects. Markers of empty folders are kept, otherwise the
+// folder would disappear. Returns the removed (or, on a dry run, removable) keys.
+#[tauri::command]
+#[specta::specta]
+pub async fn cleanup_folder_markers(
+    app: AppHandle<tauri::Wry>,
+    opts: CleanupFolderMarkersOptions,
+    state: State<'_, ConnectionMap>,
+    regions: State<'_, BucketRegionCache>,
+) -> Result<Vec<String>, String> {
+    let service = create_bucket_service(&opts.common, &opts.bucket_name, state, regions).await?;
+    let mut pager = service.pager(
+        &opts.bucket_name,
+        opts.prefix.as_deref(),
+        None,
+        opts.common.bucket_region.clone(),
+    );
+    let mut redundant = Vec::new();
+    let mut previous_marker: Option<String> = None;
+
+    // Keys below a marker sort directly after it, so a marker is redundant
+    // exactly when the next key starts with it.
+    while let Some(page) = pager
+        .next_page()
+        .await
+        .map_err(|e| format!("Failed to list objects: {}", e))?
+    {
+        for object in page {
+            if let Some(marker) = previous_marker.take() {
+                if object.key.starts_with(&marker) {
+                    redundant.push(marker);
+                }
+            }
+
+            if object.kind == ObjectKind::FolderMarker && object.size.unwrap_or(0) == 0 {
+                previous_marker = Some(object.key);
+            }
+        }
+    }
+
+    if opts.dry_run || redundant.is_empty() {
+        return Ok(redundant);
+    }
+
+    let result = service
+        .delete_objects(&opts.bucket_name, redundant.clone())
+        .await
+        .map_err(|e| format!("Failed to delete folder markers: {}", e));
+
+    let mutation = Mutation::keys(redundant.clone());
+    invalidate_caches(&app, &opts.common, &opts.bucket_name, &mutation).await;
+
+    result.map(|_| redundant)
 }
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use super::ObjectInfo;
+
+pub const DEFAULT_LISTING_TTL: Duration = Duration::from_secs(60);
+
+// Describes what one of our own operations changed in a bucket. `keys` are
+// objects that were written or removed, `subtrees` are folder prefixes that
+// were removed as a whole.
+#[derive(Default)]
+pub struct Mutation {
+    pub keys: Vec<String>,
+    pub subtrees: Vec<String>,
+}
+
+impl Mutation {
+    pub fn keys(keys: Vec<String>) -> Self {
+        Mutation {
+            keys,
+            subtrees: Vec::new(),
+        }
+    }
+
+    pub fn subtree(prefix: String) -> Self {
+        Mutation {
+            keys: Vec::new(),
+            subtrees: vec![prefix],
+        }
+    }
+
+    // A prefix is affected when something changed below it, or when it lies
+    // inside a subtree that was removed.
+    pub fn affects(&self, prefix: &str) -> bool {
+        self.keys.iter().any(|key| key.starts_with(prefix))
+            || self
+                .subtrees
+                .iter()
+                .any(|subtree| subtree.starts_with(prefix) || prefix.starts_with(subtree.as_str()))
+    }
+}
+
+// A flat listing is still keyed on the delimiter, its folder markers depend on it
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ListingKey {
+    pub connection_id: String,
+    pub bucket_name: String,
+    pub prefix: String,
+    pub delimiter: String,
+    pub flat: bool,
+}
+
+struct ListingEntries {
+    entries: HashMap<ListingKey, (Instant, Vec<ObjectInfo>)>,
+    ttl: Duration,
+    // Bumped on every invalidation so a listing that raced with a mutation
+    // doesn't get stored.
+    generation: u64,
+}
+
+#[derive(Clone)]
+pub struct ListingCache {
+    inner: Arc<Mutex<ListingEntries>>,
+}
+
+impl Default for ListingCache {
+    fn default() -> Self {
+        ListingCache {
+            inner: Arc::new(Mutex::new(ListingEntries {
+                entries: HashMap::new(),
+                ttl: DEFAULT_LISTING_TTL,
+                generation: 0,
+            })),
+        }
+    }
+}
+
+impl ListingCache {
+    pub async fn get(&self, key: &ListingKey) -> Option<Vec<ObjectInfo>> {
+        let inner = self.inner.lock().await;
+
+        inner
+            .entries
+            .get(key)
+            .filter(|(cached_at, _)| cached_at.elapsed() < inner.ttl)
+            .map(|(_, objects)| objects.clone())
+    }
+
+    pub async fn generation(&self) -> u64 {
+        self.inner.lock().await.generation
+    }
+
+    pub async fn insert(&self, key: ListingKey, objects: Vec<ObjectInfo>, generation: u64) {
+        let mut inner = self.inner.lock().await;
+
+        if inner.ttl.is_zero() || inner.generation != generation {
+            return;
+        }
+
+        inner.entries.insert(key, (Instant::now(), objects));
+    }
+
+    pub async fn invalidate(&self, connection_id: &str, bucket_name: &str, mutation: &Mutation) {
+        let mut inner = self.inner.lock().await;
+
+        inner.generation += 1;
+        inner.entries.retain(|key, _| {
+            key.connection_id != connection_id
+                || key.bucket_name != bucket_name
+                || !mutation.affects(&key.prefix)
+        });
+    }
+
+    pub async fn clear(&self, connection_id: &str, bucket_name: Option<&str>) {
+        let mut inner = self.inner.lock().await;
+
+        inner.generation += 1;
+        inner.entries.retain(|key, _| {
+            key.connection_id != connection_id
+                || bucket_name.is_some_and(|name| name != key.bucket_name)
+        });
+    }
+
+    // A TTL of zero turns the cache off
+    pub async fn set_ttl(&self, ttl: Duration) {
+        let mut inner = self.inner.lock().await;
+
+        inner.ttl = ttl;
+        inner
+            .entries
+            .retain(|_, (cached_at, _)| cached_at.elapsed() < ttl);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_keys_affect_every_enclosing_prefix() {
+        let mutation = Mutation::keys(vec!["photos/2024/beach.jpg".to_string()]);
+
+        assert!(mutation.affects(""));
+        assert!(mutation.affects("photos/"));
+        assert!(mutation.affects("photos/2024/"));
+        assert!(!mutation.affects("photos/2023/"));
+        assert!(!mutation.affects("videos/"));
+    }
+
+    #[test]
+    fn removed_subtrees_affect_parents_and_descendants() {
+        let mutation = Mutation::subtree("photos/2024/".to_string());
+
+        assert!(mutation.affects(""));
+        assert!(mutation.affects("photos/"));
+        assert!(mutation.affects("photos/2024/"));
+        assert!(mutation.affects("photos/2024/03/"));
+        assert!(!mutation.affects("photos/2023/"));
+    }
+
+    #[test]
+    fn empty_mutation_affects_nothing() {
+        assert!(!Mutation::default().affects(""));
+    }
+
+    fn key(prefix: &str) -> ListingKey {
+        ListingKey {
+            connection_id: "conn".to_string(),
+            bucket_name: "bucket".to_string(),
+            prefix: prefix.to_string(),
+            delimiter: "/".to_string(),
+            flat: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn invalidation_drops_affected_listings_and_stale_inserts() {
+        let cache = ListingCache::default();
+        let generation = cache.generation().await;
+
+        cache.insert(key("a/"), Vec::new(), generation).await;
+        cache.insert(key("b/"), Vec::new(), generation).await;
+        cache
+            .invalidate("conn", "bucket", &Mutation::keys(vec!["a/x".to_string()]))
+            .await;
+
+        assert!(cache.get(&key("a/")).await.is_none());
+        assert!(cache.get(&key("b/")).await.is_some());
+
+        // A listing that started before the invalidation is not stored
+        cache.insert(key("a/"), Vec::new(), generation).await;
+        assert!(cache.get(&key("a/")).await.is_none());
+    }
+}
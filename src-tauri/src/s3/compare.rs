@@ -0,0 +1,437 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use super::{ObjectInfo, ObjectKind};
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, PartialEq)]
+pub enum CompareStatus {
+    OnlyLeft,
+    OnlyRight,
+    SizeDiffers,
+    EtagDiffers,
+    ChecksumDiffers,
+}
+
+impl CompareStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CompareStatus::OnlyLeft => "only_left",
+            CompareStatus::OnlyRight => "only_right",
+            CompareStatus::SizeDiffers => "size_differs",
+            CompareStatus::EtagDiffers => "etag_differs",
+            CompareStatus::ChecksumDiffers => "checksum_differs",
+        }
+    }
+}
+
+// `key` is relative to the compared prefix, so it addresses the object on
+// both sides.
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct CompareEntry {
+    pub key: String,
+    pub status: CompareStatus,
+    pub left_size: Option<i64>,
+    pub right_size: Option<i64>,
+    pub left_etag: Option<String>,
+    pub right_etag: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Default)]
+pub struct CompareReport {
+    pub left_bucket: String,
+    pub left_prefix: String,
+    pub right_bucket: String,
+    pub right_prefix: String,
+    pub left_count: u64,
+    pub right_count: u64,
+    pub identical_count: u64,
+    // Same size but ETags that can't be compared (multipart or missing) and
+    // no checksum verification was requested
+    pub unverified_count: u64,
+    pub entries: Vec<CompareEntry>,
+}
+
+// ETags only identify the content when they are plain MD5s. Multipart ETags
+// depend on the part size, so S3 and R2 copies of the same file differ.
+fn comparable_etag(etag: Option<&str>) -> Option<&str> {
+    etag.filter(|etag| !etag.is_empty() && !etag.contains('-'))
+}
+
+fn entry(
+    key: String,
+    status: CompareStatus,
+    left: Option<&ObjectInfo>,
+    right: Option<&ObjectInfo>,
+) -> CompareEntry {
+    CompareEntry {
+        key,
+        status,
+        left_size: left.and_then(|object| object.size),
+        right_size: right.and_then(|object| object.size),
+        left_etag: left.and_then(|object| object.etag.clone()),
+        right_etag: right.and_then(|object| object.etag.clone()),
+    }
+}
+
+// Merges two recursive listings that arrive page by page. Both sides are
+// listed in key order, so keys can be matched without holding either listing
+// in memory.
+pub struct ListingComparer {
+    left_prefix: String,
+    right_prefix: String,
+    left: VecDeque<ObjectInfo>,
+    right: VecDeque<ObjectInfo>,
+    left_done: bool,
+    right_done: bool,
+    verify: bool,
+    // Same size, ETags not comparable, to be hashed on both sides
+    pending: Vec<CompareEntry>,
+    pub report: CompareReport,
+}
+
+impl ListingComparer {
+    pub fn new(report: CompareReport, verify: bool) -> Self {
+        ListingComparer {
+            left_prefix: report.left_prefix.clone(),
+            right_prefix: report.right_prefix.clone(),
+            left: VecDeque::new(),
+            right: VecDeque::new(),
+            left_done: false,
+            right_done: false,
+            verify,
+            pending: Vec::new(),
+            report,
+        }
+    }
+
+    pub fn needs_left(&self) -> bool {
+        self.left.is_empty() && !self.left_done
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.left_done && self.right_done && self.left.is_empty() && self.right.is_empty()
+    }
+
+    // `None` marks the end of the left listing
+    pub fn push_left(&mut self, page: Option<Vec<ObjectInfo>>) {
+        match page {
+            Some(objects) => Self::buffer(
+                &mut self.left,
+                &mut self.report.left_count,
+                &self.left_prefix,
+                objects,
+            ),
+            None => self.left_done = true,
+        }
+
+        self.merge();
+    }
+
+    pub fn push_right(&mut self, page: Option<Vec<ObjectInfo>>) {
+        match page {
+            Some(objects) => Self::buffer(
+                &mut self.right,
+                &mut self.report.right_count,
+                &self.right_prefix,
+                objects,
+            ),
+            None => self.right_done = true,
+        }
+
+        self.merge();
+    }
+
+    pub fn take_pending(&mut self) -> Vec<CompareEntry> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn add_checksum_result(&mut self, pending: CompareEntry, matches: bool) {
+        if matches {
+            self.report.identical_count += 1;
+        } else {
+            self.report.entries.push(CompareEntry {
+                status: CompareStatus::ChecksumDiffers,
+                ..pending
+            });
+        }
+    }
+
+    pub fn finish(mut self) -> CompareReport {
+        // Checksum mismatches are only known after the listings are merged
+        self.report.entries.sort_by(|a, b| a.key.cmp(&b.key));
+        self.report
+    }
+
+    // Keys are stored relative to the prefix, the prefix's own folder marker
+    // has no counterpart and is left out.
+    fn buffer(
+        buffer: &mut VecDeque<ObjectInfo>,
+        count: &mut u64,
+        prefix: &str,
+        objects: Vec<ObjectInfo>,
+    ) {
+        for mut object in objects {
+            if object.kind == ObjectKind::Prefix {
+                continue;
+            }
+
+            let Some(relative) = object.key.strip_prefix(prefix) else {
+                continue;
+            };
+
+            if relative.is_empty() {
+                continue;
+            }
+
+            object.key = relative.to_string();
+            *count += 1;
+            buffer.push_back(object);
+        }
+    }
+
+    fn merge(&mut self) {
+        loop {
+            let order = match (self.left.front(), self.right.front()) {
+                (Some(left), Some(right)) => left.key.cmp(&right.key),
+                (Some(_), None) if self.right_done => Ordering::Less,
+                (None, Some(_)) if self.left_done => Ordering::Greater,
+                _ => break,
+            };
+
+            match order {
+                Ordering::Less => {
+                    let left = self.left.pop_front().unwrap();
+                    self.report.entries.push(entry(
+                        left.key.clone(),
+                        CompareStatus::OnlyLeft,
+                        Some(&left),
+                        None,
+                    ));
+                }
+                Ordering::Greater => {
+                    let right = self.right.pop_front().unwrap();
+                    self.report.entries.push(entry(
+                        right.key.clone(),
+                        CompareStatus::OnlyRight,
+                        None,
+                        Some(&right),
+                    ));
+                }
+                Ordering::Equal => {
+                    let left = self.left.pop_front().unwrap();
+                    let right = self.right.pop_front().unwrap();
+                    self.compare(left, right);
+                }
+            }
+        }
+    }
+
+    fn compare(&mut self, left: ObjectInfo, right: ObjectInfo) {
+        let key = left.key.clone();
+
+        if left.size != right.size {
+            self.report.entries.push(entry(
+                key,
+                CompareStatus::SizeDiffers,
+                Some(&left),
+                Some(&right),
+            ));
+            return;
+        }
+
+        let left_etag = comparable_etag(left.etag.as_deref());
+        let right_etag = comparable_etag(right.etag.as_deref());
+
+        match (left_etag, right_etag) {
+            (Some(left_etag), Some(right_etag)) if left_etag == right_etag => {
+                self.report.identical_count += 1;
+            }
+            (Some(_), Some(_)) => {
+                self.report.entries.push(entry(
+                    key,
+                    CompareStatus::EtagDiffers,
+                    Some(&left),
+                    Some(&right),
+                ));
+            }
+            // Identical multipart ETags still mean identical parts
+            _ if left.etag.is_some() && left.etag == right.etag => {
+                self.report.identical_count += 1;
+            }
+            _ if self.verify => {
+                self.pending.push(entry(
+                    key,
+                    CompareStatus::ChecksumDiffers,
+                    Some(&left),
+                    Some(&right),
+                ));
+            }
+            _ => self.report.unverified_count += 1,
+        }
+    }
+}
+
+pub fn compare_to_csv(
+    report: &CompareReport,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer.write_record([
+        "status",
+        "key",
+        "left_size",
+        "right_size",
+        "left_etag",
+        "right_etag",
+    ])?;
+
+    for entry in &report.entries {
+        writer.write_record([
+            entry.status.as_str(),
+            entry.key.as_str(),
+            entry
+                .left_size
+                .map(|size| size.to_string())
+                .unwrap_or_default()
+                .as_str(),
+            entry
+                .right_size
+                .map(|size| size.to_string())
+                .unwrap_or_default()
+                .as_str(),
+            entry.left_etag.as_deref().unwrap_or_default(),
+            entry.right_etag.as_deref().unwrap_or_default(),
+        ])?;
+    }
+
+    Ok(writer.into_inner().map_err(|e| e.into_error())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::test_support;
+
+    fn object(key: &str, size: i64, etag: &str) -> ObjectInfo {
+        ObjectInfo {
+            etag: Some(etag.to_string()),
+            ..test_support::object(key, size)
+        }
+    }
+
+    fn comparer(verify: bool) -> ListingComparer {
+        ListingComparer::new(
+            CompareReport {
+                left_prefix: "left/".to_string(),
+                right_prefix: "right/".to_string(),
+                ..CompareReport::default()
+            },
+            verify,
+        )
+    }
+
+    fn statuses(report: &CompareReport) -> Vec<(&str, CompareStatus)> {
+        report
+            .entries
+            .iter()
+            .map(|entry| (entry.key.as_str(), entry.status.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn merges_listings_page_by_page() {
+        let mut comparer = comparer(false);
+
+        comparer.push_left(Some(vec![
+            object("left/", 0, "marker"),
+            object("left/a", 1, "x"),
+            object("left/b", 1, "x"),
+        ]));
+        assert!(!comparer.needs_left());
+        comparer.push_right(Some(vec![object("right/a", 1, "x")]));
+        // `b` can still show up on the right
+        assert!(comparer.report.entries.is_empty());
+
+        comparer.push_right(Some(vec![object("right/c", 2, "y")]));
+        comparer.push_left(None);
+        comparer.push_right(None);
+        assert!(comparer.is_done());
+
+        let report = comparer.finish();
+
+        assert_eq!(report.left_count, 2);
+        assert_eq!(report.right_count, 2);
+        assert_eq!(report.identical_count, 1);
+        assert_eq!(
+            statuses(&report),
+            vec![
+                ("b", CompareStatus::OnlyLeft),
+                ("c", CompareStatus::OnlyRight)
+            ]
+        );
+    }
+
+    #[test]
+    fn compares_sizes_and_etags() {
+        let mut comparer = comparer(false);
+
+        comparer.push_left(Some(vec![
+            object("left/etag", 1, "x"),
+            object("left/multipart", 1, "m-2"),
+            object("left/same-multipart", 1, "m-2"),
+            object("left/size", 1, "x"),
+        ]));
+        comparer.push_right(Some(vec![
+            object("right/etag", 1, "y"),
+            object("right/multipart", 1, "n-3"),
+            object("right/same-multipart", 1, "m-2"),
+            object("right/size", 2, "x"),
+        ]));
+        comparer.push_left(None);
+        comparer.push_right(None);
+
+        let report = comparer.finish();
+
+        assert_eq!(report.identical_count, 1);
+        assert_eq!(report.unverified_count, 1);
+        assert_eq!(
+            statuses(&report),
+            vec![
+                ("etag", CompareStatus::EtagDiffers),
+                ("size", CompareStatus::SizeDiffers)
+            ]
+        );
+    }
+
+    #[test]
+    fn unverifiable_etags_are_queued_for_checksums() {
+        let mut comparer = comparer(true);
+
+        comparer.push_left(Some(vec![
+            object("left/a", 1, "m-2"),
+            object("left/b", 1, "m-2"),
+        ]));
+        comparer.push_right(Some(vec![
+            object("right/a", 1, "n-3"),
+            object("right/b", 1, "n-3"),
+        ]));
+        comparer.push_left(None);
+        comparer.push_right(None);
+
+        let mut pending = comparer.take_pending().into_iter();
+        comparer.add_checksum_result(pending.next().unwrap(), false);
+        comparer.add_checksum_result(pending.next().unwrap(), true);
+        assert!(pending.next().is_none());
+
+        let report = comparer.finish();
+
+        assert_eq!(report.identical_count, 1);
+        assert_eq!(report.unverified_count, 0);
+        assert_eq!(
+            statuses(&report),
+            vec![("a", CompareStatus::ChecksumDiffers)]
+        );
+    }
+}
@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::{ObjectInfo, ObjectKind};
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, PartialEq)]
+pub enum DuplicateMatch {
+    // Identical single part ETags, which are the MD5 of the content
+    Md5,
+    // Identical multipart ETags, the same parts were uploaded with the same part size
+    MultipartEtag,
+    // Identical SHA-256 of the downloaded content
+    Sha256,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct DuplicateGroup {
+    pub size: i64,
+    pub matched_by: DuplicateMatch,
+    // ETag or SHA-256, depending on `matched_by`
+    pub fingerprint: String,
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct DuplicateReport {
+    pub bucket_name: String,
+    pub prefix: String,
+    pub scanned_objects: u64,
+    pub verified: bool,
+    pub groups: Vec<DuplicateGroup>,
+    // Bytes freed by keeping one copy of every group
+    pub reclaimable_bytes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct DuplicateSelection {
+    pub keep: String,
+    pub keys: Vec<String>,
+}
+
+struct Candidate {
+    key: String,
+    etag: Option<String>,
+}
+
+// Multipart ETags look like `<md5 of part md5s>-<part count>`, so they only
+// match other uploads that used the exact same part layout.
+fn is_multipart_etag(etag: &str) -> bool {
+    etag.contains('-')
+}
+
+#[derive(Default)]
+pub struct DuplicateFinder {
+    by_size: HashMap<i64, Vec<Candidate>>,
+    scanned: u64,
+}
+
+impl DuplicateFinder {
+    pub fn add(&mut self, object: &ObjectInfo) {
+        if object.kind == ObjectKind::Prefix {
+            return;
+        }
+
+        self.scanned += 1;
+
+        // Empty objects are all equal but free nothing
+        let size = object.size.unwrap_or(0);
+
+        if size == 0 {
+            return;
+        }
+
+        self.by_size.entry(size).or_default().push(Candidate {
+            key: object.key.clone(),
+            etag: object.etag.clone(),
+        });
+    }
+
+    pub fn scanned(&self) -> u64 {
+        self.scanned
+    }
+
+    pub fn etag_groups(&self) -> Vec<DuplicateGroup> {
+        let mut groups = Vec::new();
+
+        for (size, candidates) in self.size_groups() {
+            let mut by_etag: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+
+            for candidate in candidates {
+                if let Some(etag) = &candidate.etag {
+                    by_etag
+                        .entry(etag.as_str())
+                        .or_default()
+                        .push(candidate.key.clone());
+                }
+            }
+
+            for (etag, keys) in by_etag {
+                if keys.len() < 2 {
+                    continue;
+                }
+
+                groups.push(DuplicateGroup {
+                    size,
+                    matched_by: if is_multipart_etag(etag) {
+                        DuplicateMatch::MultipartEtag
+                    } else {
+                        DuplicateMatch::Md5
+                    },
+                    fingerprint: etag.to_string(),
+                    keys,
+                });
+            }
+        }
+
+        groups
+    }
+
+    // Keys whose content has to be hashed for a verified result, grouped by
+    // size. A size group with only single part ETags is narrowed down to the
+    // ETag matches, as soon as a multipart ETag is involved every object of
+    // that size is a candidate.
+    pub fn verification_sets(&self) -> Vec<(i64, Vec<String>)> {
+        let mut sets = Vec::new();
+
+        for (size, candidates) in self.size_groups() {
+            let has_multipart = candidates
+                .iter()
+                .any(|c| c.etag.as_deref().is_none_or(is_multipart_etag));
+
+            let keys: Vec<String> = if has_multipart {
+                candidates.iter().map(|c| c.key.clone()).collect()
+            } else {
+                let mut etag_counts: HashMap<&str, usize> = HashMap::new();
+
+                for candidate in candidates {
+                    if let Some(etag) = &candidate.etag {
+                        *etag_counts.entry(etag.as_str()).or_default() += 1;
+                    }
+                }
+
+                candidates
+                    .iter()
+                    .filter(|c| c.etag.as_deref().is_some_and(|etag| etag_counts[etag] > 1))
+                    .map(|c| c.key.clone())
+                    .collect()
+            };
+
+            if keys.len() > 1 {
+                sets.push((size, keys));
+            }
+        }
+
+        sets
+    }
+
+    fn size_groups(&self) -> impl Iterator<Item = (i64, &Vec<Candidate>)> {
+        self.by_size
+            .iter()
+            .filter(|(_, candidates)| candidates.len() > 1)
+            .map(|(size, candidates)| (*size, candidates))
+    }
+}
+
+pub fn hash_groups(size: i64, hashes: Vec<(String, String)>) -> Vec<DuplicateGroup> {
+    let mut by_hash: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for (key, hash) in hashes {
+        by_hash.entry(hash).or_default().push(key);
+    }
+
+    by_hash
+        .into_iter()
+        .filter(|(_, keys)| keys.len() > 1)
+        .map(|(hash, keys)| DuplicateGroup {
+            size,
+            matched_by: DuplicateMatch::Sha256,
+            fingerprint: hash,
+            keys,
+        })
+        .collect()
+}
+
+pub fn build_report(
+    bucket_name: String,
+    prefix: String,
+    scanned_objects: u64,
+    verified: bool,
+    mut groups: Vec<DuplicateGroup>,
+) -> DuplicateReport {
+    // Biggest savings first
+    groups.sort_by_key(|group| std::cmp::Reverse(group.size * (group.keys.len() as i64 - 1)));
+
+    let reclaimable_bytes = groups
+        .iter()
+        .map(|group| group.size * (group.keys.len() as i64 - 1))
+        .sum();
+
+    DuplicateReport {
+        bucket_name,
+        prefix,
+        scanned_objects,
+        verified,
+        groups,
+        reclaimable_bytes,
+    }
+}
+
+// Checked up front so a bad selection can't delete the last copy, neither
+// directly nor through another selection that lists the kept key
+pub fn keys_to_delete(selections: &[DuplicateSelection]) -> Result<Vec<String>, String> {
+    let mut keys = Vec::new();
+
+    for selection in selections {
+        if !selection.keys.contains(&selection.keep) {
+            return Err(format!(
+                "{} is not part of its duplicate group",
+                selection.keep
+            ));
+        }
+
+        keys.extend(
+            selection
+                .keys
+                .iter()
+                .filter(|key| **key != selection.keep)
+                .cloned(),
+        );
+    }
+
+    let kept: HashSet<&str> = selections.iter().map(|s| s.keep.as_str()).collect();
+
+    if let Some(key) = keys.iter().find(|key| kept.contains(key.as_str())) {
+        return Err(format!(
+            "{} is kept by one selection and deleted by another",
+            key
+        ));
+    }
+
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::test_support;
+
+    fn object(key: &str, size: i64, etag: Option<&str>) -> ObjectInfo {
+        ObjectInfo {
+            etag: etag.map(|etag| etag.to_string()),
+            ..test_support::object(key, size)
+        }
+    }
+
+    fn finder(objects: &[ObjectInfo]) -> DuplicateFinder {
+        let mut finder = DuplicateFinder::default();
+
+        for object in objects {
+            finder.add(object);
+        }
+
+        finder
+    }
+
+    fn selection(keep: &str, keys: &[&str]) -> DuplicateSelection {
+        DuplicateSelection {
+            keep: keep.to_string(),
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn groups_objects_by_size_and_etag() {
+        let finder = finder(&[
+            object("a", 10, Some("md5")),
+            object("b", 10, Some("md5")),
+            object("c", 20, Some("md5")),
+            object("d", 10, Some("other")),
+            object("e", 30, Some("part-2")),
+            object("f", 30, Some("part-2")),
+        ]);
+        let mut groups = finder.etag_groups();
+        groups.sort_by_key(|group| group.size);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].keys, vec!["a", "b"]);
+        assert_eq!(groups[0].matched_by, DuplicateMatch::Md5);
+        assert_eq!(groups[1].keys, vec!["e", "f"]);
+        assert_eq!(groups[1].matched_by, DuplicateMatch::MultipartEtag);
+    }
+
+    #[test]
+    fn skips_prefixes_and_empty_objects() {
+        let mut prefix = object("dir/", 10, Some("md5"));
+        prefix.kind = ObjectKind::Prefix;
+        let finder = finder(&[
+            prefix,
+            object("a", 10, Some("md5")),
+            object("empty-1", 0, Some("d41d")),
+            object("empty-2", 0, Some("d41d")),
+        ]);
+
+        assert_eq!(finder.scanned(), 3);
+        assert!(finder.etag_groups().is_empty());
+    }
+
+    #[test]
+    fn verification_narrows_single_part_sizes_to_etag_matches() {
+        let finder = finder(&[
+            object("a", 10, Some("x")),
+            object("b", 10, Some("x")),
+            object("c", 10, Some("y")),
+            object("d", 20, Some("p-2")),
+            object("e", 20, Some("z")),
+        ]);
+        let mut sets = finder.verification_sets();
+        sets.sort();
+
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].0, 10);
+        assert_eq!(sets[0].1, vec!["a", "b"]);
+        // A multipart ETag can hide identical content behind a different ETag
+        assert_eq!(sets[1].0, 20);
+        assert_eq!(sets[1].1, vec!["d", "e"]);
+    }
+
+    #[test]
+    fn report_sorts_by_reclaimable_bytes() {
+        let groups = [
+            hash_groups(
+                5,
+                vec![
+                    ("c".into(), "h".into()),
+                    ("d".into(), "h".into()),
+                    ("e".into(), "g".into()),
+                ],
+            ),
+            hash_groups(10, vec![("a".into(), "h".into()), ("b".into(), "h".into())]),
+        ]
+        .concat();
+        let report = build_report("bucket".into(), String::new(), 5, true, groups);
+
+        assert_eq!(report.groups[0].keys, vec!["a", "b"]);
+        assert_eq!(report.groups[1].keys, vec!["c", "d"]);
+        assert_eq!(report.reclaimable_bytes, 15);
+    }
+
+    #[test]
+    fn keys_to_delete_keeps_one_copy_per_group() {
+        let keys = keys_to_delete(&[
+            selection("a", &["a", "b", "c"]),
+            selection("d", &["d", "e"]),
+        ])
+        .unwrap();
+
+        assert_eq!(keys, vec!["b", "c", "e"]);
+    }
+
+    #[test]
+    fn keys_to_delete_rejects_unsafe_selections() {
+        assert!(keys_to_delete(&[selection("x", &["a", "b"])]).is_err());
+        assert!(
+            keys_to_delete(&[selection("a", &["a", "b"]), selection("b", &["b", "c"])]).is_err()
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::versions::ObjectVersion;
+use super::ObjectInfo;
+
+// What emptying a bucket removes. On a dry run these are the counts found,
+// otherwise the counts processed so far.
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Default)]
+pub struct EmptyBucketSummary {
+    // Versioned buckets are emptied version by version, including the
+    // versions hidden behind delete markers
+    pub versioned: bool,
+    pub object_count: u64,
+    pub noncurrent_version_count: u64,
+    pub delete_marker_count: u64,
+    pub total_size: i64,
+    pub multipart_upload_count: u64,
+}
+
+impl EmptyBucketSummary {
+    pub fn add_version(&mut self, version: &ObjectVersion) {
+        if version.is_delete_marker {
+            self.delete_marker_count += 1;
+            return;
+        }
+
+        if version.is_latest {
+            self.object_count += 1;
+        } else {
+            self.noncurrent_version_count += 1;
+        }
+
+        self.total_size += version.size.unwrap_or(0);
+    }
+
+    pub fn add_object(&mut self, object: &ObjectInfo) {
+        self.object_count += 1;
+        self.total_size += object.size.unwrap_or(0);
+    }
+}
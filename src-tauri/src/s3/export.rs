@@ -0,0 +1,285 @@
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+use parquet::basic::Compression;
+use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedFileWriter, SerializedRowGroupWriter};
+use parquet::schema::parser::parse_message_type;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::ObjectInfo;
+
+// Rows buffered per Parquet row group, larger groups compress better
+const ROW_GROUP_SIZE: usize = 50_000;
+
+const PARQUET_SCHEMA: &str = "
+message listing {
+    OPTIONAL BYTE_ARRAY key (UTF8);
+    OPTIONAL INT64 size;
+    OPTIONAL INT64 last_modified (TIMESTAMP(MILLIS, true));
+    OPTIONAL BYTE_ARRAY storage_class (UTF8);
+    OPTIONAL BYTE_ARRAY etag (UTF8);
+    OPTIONAL BYTE_ARRAY url (UTF8);
+}
+";
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub enum ListingFormat {
+    Csv,
+    JsonLines,
+    Parquet,
+}
+
+#[derive(Serialize)]
+struct ListingRow<'a> {
+    key: &'a str,
+    size: Option<i64>,
+    last_modified: Option<&'a str>,
+    storage_class: Option<&'a str>,
+    etag: Option<&'a str>,
+    url: &'a str,
+}
+
+impl<'a> From<&'a ObjectInfo> for ListingRow<'a> {
+    fn from(object: &'a ObjectInfo) -> Self {
+        ListingRow {
+            key: &object.key,
+            size: object.size,
+            last_modified: object.last_modified.as_deref(),
+            storage_class: object.storage_class.as_deref(),
+            etag: object.etag.as_deref(),
+            url: &object.url,
+        }
+    }
+}
+
+// Exports are written next to the target and only renamed onto it once
+// complete, so a failed export never replaces an existing file
+pub fn partial_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".partial");
+    path.with_file_name(file_name)
+}
+
+// Writes listing pages to disk as they arrive, only Parquet keeps up to one
+// row group in memory.
+pub enum ListingWriter {
+    Csv(csv::Writer<BufWriter<File>>),
+    JsonLines(BufWriter<File>),
+    Parquet(ParquetListingWriter),
+}
+
+impl ListingWriter {
+    pub fn create(
+        path: &Path,
+        format: &ListingFormat,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let file = File::create(path)?;
+
+        let writer = match format {
+            ListingFormat::Csv => {
+                ListingWriter::Csv(csv::Writer::from_writer(BufWriter::new(file)))
+            }
+            ListingFormat::JsonLines => ListingWriter::JsonLines(BufWriter::new(file)),
+            ListingFormat::Parquet => ListingWriter::Parquet(ParquetListingWriter::new(file)?),
+        };
+
+        Ok(writer)
+    }
+
+    pub fn write(
+        &mut self,
+        objects: &[ObjectInfo],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            ListingWriter::Csv(writer) => {
+                for object in objects {
+                    writer.serialize(ListingRow::from(object))?;
+                }
+            }
+            ListingWriter::JsonLines(writer) => {
+                for object in objects {
+                    serde_json::to_writer(&mut *writer, &ListingRow::from(object))?;
+                    writer.write_all(b"\n")?;
+                }
+            }
+            ListingWriter::Parquet(writer) => writer.write(objects)?,
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            ListingWriter::Csv(mut writer) => writer.flush()?,
+            ListingWriter::JsonLines(mut writer) => writer.flush()?,
+            ListingWriter::Parquet(writer) => writer.finish()?,
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ParquetListingWriter {
+    writer: SerializedFileWriter<BufWriter<File>>,
+    rows: Vec<ObjectInfo>,
+}
+
+impl ParquetListingWriter {
+    fn new(file: File) -> Result<Self, ParquetError> {
+        let schema = Arc::new(parse_message_type(PARQUET_SCHEMA)?);
+        let properties = Arc::new(
+            WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build(),
+        );
+
+        Ok(ParquetListingWriter {
+            writer: SerializedFileWriter::new(BufWriter::new(file), schema, properties)?,
+            rows: Vec::with_capacity(ROW_GROUP_SIZE),
+        })
+    }
+
+    fn write(&mut self, objects: &[ObjectInfo]) -> Result<(), ParquetError> {
+        self.rows.extend_from_slice(objects);
+
+        if self.rows.len() >= ROW_GROUP_SIZE {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(), ParquetError> {
+        self.flush()?;
+        self.writer.close()?;
+
+        Ok(())
+    }
+
+    // Columns have to be written in schema order
+    fn flush(&mut self) -> Result<(), ParquetError> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+
+        let rows = mem::take(&mut self.rows);
+        let mut row_group = self.writer.next_row_group()?;
+
+        write_strings(&mut row_group, rows.iter().map(|o| Some(o.key.as_str())))?;
+        write_longs(&mut row_group, rows.iter().map(|o| o.size))?;
+        write_longs(
+            &mut row_group,
+            rows.iter().map(|o| {
+                o.last_modified
+                    .as_deref()
+                    .and_then(|date| DateTime::from_str(date, DateTimeFormat::DateTime).ok())
+                    .and_then(|date| date.to_millis().ok())
+            }),
+        )?;
+        write_strings(
+            &mut row_group,
+            rows.iter().map(|o| o.storage_class.as_deref()),
+        )?;
+        write_strings(&mut row_group, rows.iter().map(|o| o.etag.as_deref()))?;
+        write_strings(&mut row_group, rows.iter().map(|o| Some(o.url.as_str())))?;
+
+        row_group.close()?;
+
+        Ok(())
+    }
+}
+
+// Optional columns take a definition level per row and a value only for the
+// rows that have one.
+fn write_strings<'a>(
+    row_group: &mut SerializedRowGroupWriter<'_, BufWriter<File>>,
+    values: impl Iterator<Item = Option<&'a str>>,
+) -> Result<(), ParquetError> {
+    let mut column = row_group
+        .next_column()?
+        .ok_or_else(|| ParquetError::General("Missing column".to_string()))?;
+    let mut present = Vec::new();
+    let mut levels = Vec::new();
+
+    for value in values {
+        levels.push(i16::from(value.is_some()));
+        present.extend(value.map(ByteArray::from));
+    }
+
+    column
+        .typed::<ByteArrayType>()
+        .write_batch(&present, Some(&levels), None)?;
+    column.close()
+}
+
+fn write_longs(
+    row_group: &mut SerializedRowGroupWriter<'_, BufWriter<File>>,
+    values: impl Iterator<Item = Option<i64>>,
+) -> Result<(), ParquetError> {
+    let mut column = row_group
+        .next_column()?
+        .ok_or_else(|| ParquetError::General("Missing column".to_string()))?;
+    let mut present = Vec::new();
+    let mut levels = Vec::new();
+
+    for value in values {
+        levels.push(i16::from(value.is_some()));
+        present.extend(value);
+    }
+
+    column
+        .typed::<Int64Type>()
+        .write_batch(&present, Some(&levels), None)?;
+    column.close()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::test_support;
+
+    fn object(key: &str, size: i64) -> ObjectInfo {
+        ObjectInfo {
+            url: format!("https://bucket.example.com/{}", key),
+            etag: Some("\"abc\"".to_string()),
+            ..test_support::object(key, size)
+        }
+    }
+
+    #[test]
+    fn partial_path_sits_next_to_the_target() {
+        assert_eq!(
+            partial_path(Path::new("/exports/listing.csv")),
+            PathBuf::from("/exports/listing.csv.partial")
+        );
+    }
+
+    #[test]
+    fn json_lines_export_writes_one_row_per_object() {
+        let path = std::env::temp_dir().join(format!("listing-{}.jsonl", std::process::id()));
+        let mut writer = ListingWriter::create(&path, &ListingFormat::JsonLines).unwrap();
+
+        writer.write(&[object("a.txt", 1)]).unwrap();
+        writer.write(&[object("b/c.txt", 2)]).unwrap();
+        writer.finish().unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let rows: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["key"], "a.txt");
+        assert_eq!(rows[1]["size"], 2);
+        assert_eq!(rows[1]["url"], "https://bucket.example.com/b/c.txt");
+    }
+}
@@ -0,0 +1,1140 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::inventory::{self, InventoryManifest};
+use super::report::BucketReport;
+use super::search::{KeyPattern, SearchFilters};
+use super::url;
+use super::{ObjectInfo, ObjectKind};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS inventories (
+    id INTEGER PRIMARY KEY,
+    connection_id TEXT NOT NULL,
+    bucket_name TEXT NOT NULL,
+    url_base TEXT NOT NULL,
+    manifest_bucket TEXT NOT NULL,
+    manifest_key TEXT NOT NULL,
+    inventory_date TEXT NOT NULL,
+    imported_at TEXT NOT NULL,
+    object_count INTEGER NOT NULL DEFAULT 0,
+    total_size INTEGER NOT NULL DEFAULT 0,
+    complete INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS inventory_objects (
+    inventory_id INTEGER NOT NULL REFERENCES inventories (id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    size INTEGER,
+    last_modified TEXT,
+    etag TEXT,
+    storage_class TEXT,
+    PRIMARY KEY (inventory_id, key)
+) WITHOUT ROWID;
+
+CREATE TABLE IF NOT EXISTS indexed_buckets (
+    id INTEGER PRIMARY KEY,
+    connection_id TEXT NOT NULL,
+    bucket_name TEXT NOT NULL,
+    url_base TEXT NOT NULL,
+    generation INTEGER NOT NULL DEFAULT 0,
+    needs_full_crawl INTEGER NOT NULL DEFAULT 1,
+    indexed_at TEXT,
+    object_count INTEGER NOT NULL DEFAULT 0,
+    total_size INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (connection_id, bucket_name)
+);
+
+CREATE TABLE IF NOT EXISTS indexed_objects (
+    bucket_id INTEGER NOT NULL REFERENCES indexed_buckets (id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    size INTEGER,
+    last_modified TEXT,
+    etag TEXT,
+    storage_class TEXT,
+    generation INTEGER NOT NULL,
+    PRIMARY KEY (bucket_id, key)
+) WITHOUT ROWID;
+
+CREATE TABLE IF NOT EXISTS index_dirty_prefixes (
+    bucket_id INTEGER NOT NULL REFERENCES indexed_buckets (id) ON DELETE CASCADE,
+    prefix TEXT NOT NULL,
+    PRIMARY KEY (bucket_id, prefix)
+) WITHOUT ROWID;
+";
+
+const OBJECT_COLUMNS: &str = "key, size, last_modified, etag, storage_class";
+
+// Past this many pending changes a full crawl is cheaper than relisting each
+const MAX_DIRTY_PREFIXES: i64 = 100;
+
+// Rows read per lock while searching
+const SEARCH_BATCH_SIZE: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct InventoryInfo {
+    pub id: i64,
+    // The connection's identity, which outlives its session id
+    pub connection_id: String,
+    pub bucket_name: String,
+    pub manifest_bucket: String,
+    pub manifest_key: String,
+    // When S3 generated the inventory, everything read from it is as of then
+    pub inventory_date: String,
+    pub imported_at: String,
+    pub object_count: u64,
+    pub total_size: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct InventoryListing {
+    pub inventory: InventoryInfo,
+    pub objects: Vec<ObjectInfo>,
+    // Set when the page is full, pass it back as `start_after`
+    pub next_start_after: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct InventorySearchResult {
+    pub inventory: InventoryInfo,
+    pub objects: Vec<ObjectInfo>,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct InventoryReport {
+    pub inventory: InventoryInfo,
+    pub report: BucketReport,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct IndexedBucket {
+    // The connection's identity, which outlives its session id
+    pub connection_id: String,
+    pub bucket_name: String,
+    // None until the first crawl finished
+    pub indexed_at: Option<String>,
+    pub object_count: u64,
+    pub total_size: i64,
+    // Prefixes changed by this app since the last crawl
+    pub pending_changes: u64,
+    pub needs_full_crawl: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct IndexMatch {
+    pub connection_id: String,
+    pub bucket_name: String,
+    pub object: ObjectInfo,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct IndexSearchResult {
+    pub matches: Vec<IndexMatch>,
+    pub truncated: bool,
+}
+
+// What one crawl relists. An empty prefix stands for the whole bucket.
+pub struct CrawlPlan {
+    pub bucket_id: i64,
+    pub generation: i64,
+    pub prefixes: Vec<String>,
+}
+
+// Marks a bucket as being crawled until dropped
+pub struct CrawlClaim {
+    crawling: Arc<Mutex<HashSet<i64>>>,
+    bucket_id: i64,
+}
+
+impl Drop for CrawlClaim {
+    fn drop(&mut self) {
+        self.crawling
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&self.bucket_id);
+    }
+}
+
+pub struct NewInventory {
+    pub connection_id: String,
+    pub bucket_name: String,
+    // Bucket URL or public base URL, object URLs are built from it
+    pub url_base: String,
+    pub manifest_bucket: String,
+    pub manifest_key: String,
+    pub inventory_date: String,
+    pub imported_at: String,
+}
+
+// Local SQLite database next to the app data. Access is serialized through
+// one connection, callers run the methods on a blocking thread. The database
+// is opened on first use, so a broken file only fails the index commands
+// instead of the app start.
+#[derive(Clone)]
+pub struct ObjectIndex {
+    path: PathBuf,
+    connection: Arc<Mutex<Option<Connection>>>,
+    crawling: Arc<Mutex<HashSet<i64>>>,
+}
+
+// Lock on an opened connection
+struct IndexConnection<'a>(MutexGuard<'a, Option<Connection>>);
+
+impl Deref for IndexConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.0
+            .as_ref()
+            .expect("connection is opened before locking")
+    }
+}
+
+impl DerefMut for IndexConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.0
+            .as_mut()
+            .expect("connection is opened before locking")
+    }
+}
+
+impl ObjectIndex {
+    pub fn new(path: PathBuf) -> Self {
+        ObjectIndex {
+            path,
+            connection: Arc::new(Mutex::new(None)),
+            crawling: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    // Two crawls of the same bucket would bump the generation under each
+    // other and drop each other's objects as stale
+    pub fn claim_crawl(&self, bucket_id: i64) -> Option<CrawlClaim> {
+        let mut crawling = self
+            .crawling
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        crawling.insert(bucket_id).then(|| CrawlClaim {
+            crawling: self.crawling.clone(),
+            bucket_id,
+        })
+    }
+
+    fn open(&self) -> rusqlite::Result<Connection> {
+        let connection = Connection::open(&self.path)?;
+
+        connection.pragma_update(None, "journal_mode", "WAL")?;
+        connection.pragma_update(None, "foreign_keys", true)?;
+        connection.execute_batch(SCHEMA)?;
+
+        // Imports interrupted by a crash or quit are not resumable
+        connection.execute("DELETE FROM inventories WHERE complete = 0", [])?;
+
+        Ok(connection)
+    }
+
+    // A failed open is retried on the next call
+    fn lock(&self) -> rusqlite::Result<IndexConnection<'_>> {
+        let mut connection = self
+            .connection
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if connection.is_none() {
+            *connection = Some(self.open()?);
+        }
+
+        Ok(IndexConnection(connection))
+    }
+
+    pub fn create_inventory(&self, inventory: &NewInventory) -> rusqlite::Result<i64> {
+        let connection = self.lock()?;
+
+        connection.execute(
+            "INSERT INTO inventories (connection_id, bucket_name, url_base, manifest_bucket, manifest_key, inventory_date, imported_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                inventory.connection_id,
+                inventory.bucket_name,
+                inventory.url_base,
+                inventory.manifest_bucket,
+                inventory.manifest_key,
+                inventory.inventory_date,
+                inventory.imported_at,
+            ],
+        )?;
+
+        Ok(connection.last_insert_rowid())
+    }
+
+    // Loads one data file of the inventory in a single transaction and
+    // returns the number of objects it contained.
+    pub fn import_inventory_file(
+        &self,
+        inventory_id: i64,
+        manifest: &InventoryManifest,
+        path: &Path,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut connection = self.lock()?;
+        let transaction = connection.transaction()?;
+        let mut object_count: u64 = 0;
+        let mut total_size: i64 = 0;
+
+        {
+            let mut insert = transaction.prepare_cached(
+                "INSERT OR REPLACE INTO inventory_objects (inventory_id, key, size, last_modified, etag, storage_class)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+
+            inventory::read_records(manifest, path, |record| {
+                insert.execute(params![
+                    inventory_id,
+                    record.key,
+                    record.size,
+                    record.last_modified,
+                    record.etag,
+                    record.storage_class,
+                ])?;
+
+                object_count += 1;
+                total_size += record.size.unwrap_or(0);
+
+                Ok(())
+            })?;
+        }
+
+        transaction.execute(
+            "UPDATE inventories SET object_count = object_count + ?2, total_size = total_size + ?3 WHERE id = ?1",
+            params![inventory_id, object_count, total_size],
+        )?;
+        transaction.commit()?;
+
+        Ok(object_count)
+    }
+
+    pub fn complete_inventory(&self, inventory_id: i64) -> rusqlite::Result<()> {
+        self.lock()?.execute(
+            "UPDATE inventories SET complete = 1 WHERE id = ?1",
+            params![inventory_id],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn delete_inventory(&self, inventory_id: i64) -> rusqlite::Result<()> {
+        self.lock()?.execute(
+            "DELETE FROM inventories WHERE id = ?1",
+            params![inventory_id],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn list_inventories(&self) -> rusqlite::Result<Vec<InventoryInfo>> {
+        let connection = self.lock()?;
+        let mut statement = connection.prepare(
+            "SELECT id, connection_id, bucket_name, manifest_bucket, manifest_key, inventory_date, imported_at, object_count, total_size
+             FROM inventories WHERE complete = 1 ORDER BY bucket_name, inventory_date DESC",
+        )?;
+
+        let inventories = statement
+            .query_map([], inventory_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(inventories)
+    }
+
+    // Returns the inventory along with the base for object URLs
+    pub fn inventory(
+        &self,
+        inventory_id: i64,
+    ) -> rusqlite::Result<Option<(InventoryInfo, String)>> {
+        self.lock()?
+            .query_row(
+                "SELECT id, connection_id, bucket_name, manifest_bucket, manifest_key, inventory_date, imported_at, object_count, total_size, url_base
+                 FROM inventories WHERE id = ?1 AND complete = 1",
+                params![inventory_id],
+                |row| Ok((inventory_from_row(row)?, row.get(9)?)),
+            )
+            .optional()
+    }
+
+    // Lists one folder level below `prefix`. Every returned entry costs one
+    // index lookup, whole subtrees are skipped when a folder is found.
+    pub fn browse_inventory(
+        &self,
+        inventory_id: i64,
+        url_base: &str,
+        prefix: &str,
+        delimiter: &str,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> rusqlite::Result<(Vec<ObjectInfo>, Option<String>)> {
+        let connection = self.lock()?;
+        let mut from = connection.prepare_cached(&format!(
+            "SELECT {} FROM inventory_objects WHERE inventory_id = ?1 AND key >= ?2 ORDER BY key LIMIT 1",
+            OBJECT_COLUMNS
+        ))?;
+        let mut after = connection.prepare_cached(&format!(
+            "SELECT {} FROM inventory_objects WHERE inventory_id = ?1 AND key > ?2 ORDER BY key LIMIT 1",
+            OBJECT_COLUMNS
+        ))?;
+
+        // (key, inclusive)
+        let mut cursor = match start_after {
+            Some(folder) if folder.ends_with(delimiter) => match prefix_end(folder) {
+                Some(end) => (end, true),
+                None => return Ok((Vec::new(), None)),
+            },
+            Some(key) => (key.to_string(), false),
+            None => (prefix.to_string(), true),
+        };
+        let mut objects = Vec::new();
+
+        while objects.len() < limit {
+            let statement = if cursor.1 { &mut from } else { &mut after };
+            let object = statement
+                .query_row(params![inventory_id, cursor.0], |row| {
+                    object_from_row(row, url_base, delimiter)
+                })
+                .optional()?;
+
+            let Some(object) = object.filter(|object| object.key.starts_with(prefix)) else {
+                return Ok((objects, None));
+            };
+
+            let relative = &object.key[prefix.len()..];
+
+            match relative.find(delimiter) {
+                Some(index) => {
+                    let folder = format!("{}{}", prefix, &relative[..index + delimiter.len()]);
+                    let Some(end) = prefix_end(&folder) else {
+                        return Ok((objects, None));
+                    };
+
+                    cursor = (end, true);
+                    objects.push(ObjectInfo {
+                        key: folder.clone(),
+                        size: None,
+                        last_modified: None,
+                        storage_class: None,
+                        is_folder: true,
+                        kind: ObjectKind::Prefix,
+                        url: url::object_url(url_base, None, &folder),
+                        etag: None,
+                        checksum_algorithms: Vec::new(),
+                        owner: None,
+                        restore_status: None,
+                    });
+                }
+                None => {
+                    cursor = (object.key.clone(), false);
+
+                    // The marker of the listed folder itself
+                    if !relative.is_empty() {
+                        objects.push(object);
+                    }
+                }
+            }
+        }
+
+        let next_start_after = objects.last().map(|object| object.key.clone());
+
+        Ok((objects, next_start_after))
+    }
+
+    // Visits every object below `prefix` in key order until `visit` returns false
+    pub fn scan_inventory(
+        &self,
+        inventory_id: i64,
+        url_base: &str,
+        prefix: &str,
+        delimiter: &str,
+        mut visit: impl FnMut(ObjectInfo) -> bool,
+    ) -> rusqlite::Result<()> {
+        let end = prefix_end(prefix);
+        let mut after: Option<String> = None;
+
+        // Batched like search_indexed_objects, the lock isn't held while a
+        // large inventory is scanned
+        loop {
+            let batch = {
+                let connection = self.lock()?;
+                let mut statement = connection.prepare_cached(&format!(
+                    "SELECT {} FROM inventory_objects
+                     WHERE inventory_id = ?1 AND key >= ?2
+                       AND (?3 IS NULL OR key < ?3)
+                       AND (?4 IS NULL OR key > ?4)
+                     ORDER BY key
+                     LIMIT ?5",
+                    OBJECT_COLUMNS
+                ))?;
+
+                let batch = statement
+                    .query_map(
+                        params![inventory_id, prefix, end, after, SEARCH_BATCH_SIZE],
+                        |row| object_from_row(row, url_base, delimiter),
+                    )?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+
+                batch
+            };
+
+            let done = batch.len() < SEARCH_BATCH_SIZE;
+            after = batch.last().map(|last| last.key.clone());
+
+            for object in batch {
+                if !visit(object) {
+                    return Ok(());
+                }
+            }
+
+            if done {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Opts a bucket into indexing, or refreshes its URL base when it already is
+    pub fn add_indexed_bucket(
+        &self,
+        connection_id: &str,
+        bucket_name: &str,
+        url_base: &str,
+    ) -> rusqlite::Result<i64> {
+        let connection = self.lock()?;
+
+        connection.execute(
+            "INSERT INTO indexed_buckets (connection_id, bucket_name, url_base) VALUES (?1, ?2, ?3)
+             ON CONFLICT (connection_id, bucket_name) DO UPDATE SET url_base = excluded.url_base",
+            params![connection_id, bucket_name, url_base],
+        )?;
+
+        connection.query_row(
+            "SELECT id FROM indexed_buckets WHERE connection_id = ?1 AND bucket_name = ?2",
+            params![connection_id, bucket_name],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn remove_indexed_bucket(
+        &self,
+        connection_id: &str,
+        bucket_name: &str,
+    ) -> rusqlite::Result<()> {
+        self.lock()?.execute(
+            "DELETE FROM indexed_buckets WHERE connection_id = ?1 AND bucket_name = ?2",
+            params![connection_id, bucket_name],
+        )?;
+
+        Ok(())
+    }
+
+    // Drops the buckets of every connection not in `connection_ids`
+    pub fn prune_indexed_buckets(
+        &self,
+        connection_ids: &[String],
+    ) -> rusqlite::Result<Vec<IndexedBucket>> {
+        let connection = self.lock()?;
+        let mut statement = connection.prepare(&format!(
+            "SELECT {} FROM indexed_buckets b ORDER BY b.connection_id, b.bucket_name",
+            INDEXED_BUCKET_COLUMNS
+        ))?;
+
+        let orphaned: Vec<IndexedBucket> = statement
+            .query_map([], indexed_bucket_from_row)?
+            .filter(|bucket| {
+                bucket.as_ref().map_or(true, |bucket| {
+                    !connection_ids.contains(&bucket.connection_id)
+                })
+            })
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        for bucket in &orphaned {
+            connection.execute(
+                "DELETE FROM indexed_buckets WHERE connection_id = ?1 AND bucket_name = ?2",
+                params![bucket.connection_id, bucket.bucket_name],
+            )?;
+        }
+
+        Ok(orphaned)
+    }
+
+    pub fn list_indexed_buckets(&self) -> rusqlite::Result<Vec<IndexedBucket>> {
+        let connection = self.lock()?;
+        let mut statement = connection.prepare(&format!(
+            "SELECT {} FROM indexed_buckets b ORDER BY b.connection_id, b.bucket_name",
+            INDEXED_BUCKET_COLUMNS
+        ))?;
+
+        let buckets = statement
+            .query_map([], indexed_bucket_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(buckets)
+    }
+
+    pub fn indexed_bucket(&self, bucket_id: i64) -> rusqlite::Result<Option<IndexedBucket>> {
+        self.lock()?
+            .query_row(
+                &format!(
+                    "SELECT {} FROM indexed_buckets b WHERE b.id = ?1",
+                    INDEXED_BUCKET_COLUMNS
+                ),
+                params![bucket_id],
+                indexed_bucket_from_row,
+            )
+            .optional()
+    }
+
+    // Remembers what our own mutations touched so the next crawl only
+    // relists those prefixes. Buckets that aren't indexed are ignored.
+    pub fn mark_dirty(
+        &self,
+        connection_id: &str,
+        bucket_name: &str,
+        prefixes: &[String],
+    ) -> rusqlite::Result<()> {
+        let mut connection = self.lock()?;
+        let transaction = connection.transaction()?;
+
+        let bucket_id: Option<i64> = transaction
+            .query_row(
+                "SELECT id FROM indexed_buckets WHERE connection_id = ?1 AND bucket_name = ?2",
+                params![connection_id, bucket_name],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let Some(bucket_id) = bucket_id else {
+            return Ok(());
+        };
+
+        {
+            let mut insert = transaction.prepare_cached(
+                "INSERT OR IGNORE INTO index_dirty_prefixes (bucket_id, prefix) VALUES (?1, ?2)",
+            )?;
+
+            for prefix in prefixes {
+                insert.execute(params![bucket_id, prefix])?;
+            }
+        }
+
+        let dirty_count: i64 = transaction.query_row(
+            "SELECT COUNT(*) FROM index_dirty_prefixes WHERE bucket_id = ?1",
+            params![bucket_id],
+            |row| row.get(0),
+        )?;
+
+        if dirty_count > MAX_DIRTY_PREFIXES || prefixes.iter().any(|prefix| prefix.is_empty()) {
+            transaction.execute(
+                "UPDATE indexed_buckets SET needs_full_crawl = 1 WHERE id = ?1",
+                params![bucket_id],
+            )?;
+            transaction.execute(
+                "DELETE FROM index_dirty_prefixes WHERE bucket_id = ?1",
+                params![bucket_id],
+            )?;
+        }
+
+        transaction.commit()
+    }
+
+    // Takes the pending work of a bucket. Changes marked while the crawl runs
+    // stay queued for the next one.
+    pub fn start_crawl(&self, bucket_id: i64, full: bool) -> rusqlite::Result<CrawlPlan> {
+        let mut connection = self.lock()?;
+        let transaction = connection.transaction()?;
+
+        let (generation, needs_full_crawl): (i64, bool) = transaction.query_row(
+            "UPDATE indexed_buckets SET generation = generation + 1 WHERE id = ?1
+             RETURNING generation, needs_full_crawl",
+            params![bucket_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let prefixes = if full || needs_full_crawl {
+            transaction.execute(
+                "UPDATE indexed_buckets SET needs_full_crawl = 0 WHERE id = ?1",
+                params![bucket_id],
+            )?;
+
+            vec![String::new()]
+        } else {
+            let mut statement = transaction.prepare(
+                "SELECT prefix FROM index_dirty_prefixes WHERE bucket_id = ?1 ORDER BY prefix",
+            )?;
+            let prefixes = statement
+                .query_map(params![bucket_id], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+
+            // A prefix inside another one is covered by relisting the outer one
+            let mut outermost: Vec<String> = Vec::new();
+
+            for prefix in prefixes {
+                if !outermost
+                    .last()
+                    .is_some_and(|outer| prefix.starts_with(outer.as_str()))
+                {
+                    outermost.push(prefix);
+                }
+            }
+
+            outermost
+        };
+
+        transaction.execute(
+            "DELETE FROM index_dirty_prefixes WHERE bucket_id = ?1",
+            params![bucket_id],
+        )?;
+        transaction.commit()?;
+
+        Ok(CrawlPlan {
+            bucket_id,
+            generation,
+            prefixes,
+        })
+    }
+
+    // Puts prefixes of a failed or cancelled crawl back in the queue
+    pub fn requeue(&self, bucket_id: i64, prefixes: &[String]) -> rusqlite::Result<()> {
+        let connection = self.lock()?;
+
+        if prefixes.iter().any(|prefix| prefix.is_empty()) {
+            connection.execute(
+                "UPDATE indexed_buckets SET needs_full_crawl = 1 WHERE id = ?1",
+                params![bucket_id],
+            )?;
+
+            return Ok(());
+        }
+
+        let mut insert = connection.prepare_cached(
+            "INSERT OR IGNORE INTO index_dirty_prefixes (bucket_id, prefix) VALUES (?1, ?2)",
+        )?;
+
+        for prefix in prefixes {
+            insert.execute(params![bucket_id, prefix])?;
+        }
+
+        Ok(())
+    }
+
+    pub fn upsert_indexed_objects(
+        &self,
+        bucket_id: i64,
+        generation: i64,
+        objects: &[ObjectInfo],
+    ) -> rusqlite::Result<()> {
+        let mut connection = self.lock()?;
+        let transaction = connection.transaction()?;
+
+        {
+            let mut upsert = transaction.prepare_cached(
+                "INSERT OR REPLACE INTO indexed_objects (bucket_id, key, size, last_modified, etag, storage_class, generation)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+
+            for object in objects.iter().filter(|o| o.kind != ObjectKind::Prefix) {
+                upsert.execute(params![
+                    bucket_id,
+                    object.key,
+                    object.size,
+                    object.last_modified,
+                    object.etag,
+                    object.storage_class,
+                    generation,
+                ])?;
+            }
+        }
+
+        transaction.commit()
+    }
+
+    // Objects below a fully relisted prefix that weren't seen are gone
+    pub fn remove_stale_objects(
+        &self,
+        bucket_id: i64,
+        generation: i64,
+        prefix: &str,
+    ) -> rusqlite::Result<()> {
+        self.lock()?.execute(
+            "DELETE FROM indexed_objects
+             WHERE bucket_id = ?1 AND key >= ?2 AND (?3 IS NULL OR key < ?3) AND generation < ?4",
+            params![bucket_id, prefix, prefix_end(prefix), generation],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn finish_crawl(&self, bucket_id: i64, indexed_at: &str) -> rusqlite::Result<()> {
+        self.lock()?.execute(
+            "UPDATE indexed_buckets SET
+                indexed_at = ?2,
+                object_count = (SELECT COUNT(*) FROM indexed_objects WHERE bucket_id = ?1),
+                total_size = (SELECT COALESCE(SUM(size), 0) FROM indexed_objects WHERE bucket_id = ?1)
+             WHERE id = ?1",
+            params![bucket_id, indexed_at],
+        )?;
+
+        Ok(())
+    }
+
+    // Runs the cheap parts of the filters in SQL, `visit` gets the candidates
+    // in bucket and key order until it returns false.
+    pub fn search_indexed_objects(
+        &self,
+        filters: &SearchFilters,
+        delimiter: &str,
+        mut visit: impl FnMut(IndexMatch) -> bool,
+    ) -> rusqlite::Result<()> {
+        // SQLite only lowercases ASCII, other patterns are left to the caller
+        let (substring, case_sensitive) = match &filters.pattern {
+            Some(KeyPattern::Substring(substring))
+                if filters.case_sensitive || substring.is_ascii() =>
+            {
+                (Some(substring.as_str()), filters.case_sensitive)
+            }
+            _ => (None, true),
+        };
+
+        // Rows are read in batches and the lock is released in between, so
+        // a long search doesn't stall crawls and other index commands
+        let mut after: Option<(String, String, String)> = None;
+
+        loop {
+            let batch = {
+                let connection = self.lock()?;
+                let mut statement = connection.prepare_cached(
+                    "SELECT o.key, o.size, o.last_modified, o.etag, o.storage_class, b.url_base, b.connection_id, b.bucket_name
+                     FROM indexed_objects o JOIN indexed_buckets b ON b.id = o.bucket_id
+                     WHERE (?1 IS NULL OR (CASE WHEN ?2 THEN instr(o.key, ?1) ELSE instr(lower(o.key), lower(?1)) END) > 0)
+                       AND (?3 IS NULL OR o.size >= ?3)
+                       AND (?4 IS NULL OR o.size <= ?4)
+                       AND (?5 IS NULL OR (b.connection_id, b.bucket_name, o.key) > (?5, ?6, ?7))
+                     ORDER BY b.connection_id, b.bucket_name, o.key
+                     LIMIT ?8",
+                )?;
+                let (after_connection, after_bucket, after_key) = match &after {
+                    Some((connection_id, bucket_name, key)) => (
+                        Some(connection_id.as_str()),
+                        Some(bucket_name.as_str()),
+                        Some(key.as_str()),
+                    ),
+                    None => (None, None, None),
+                };
+
+                let batch = statement
+                    .query_map(
+                        params![
+                            substring,
+                            case_sensitive,
+                            filters.min_size,
+                            filters.max_size,
+                            after_connection,
+                            after_bucket,
+                            after_key,
+                            SEARCH_BATCH_SIZE
+                        ],
+                        |row| {
+                            let url_base: String = row.get(5)?;
+
+                            Ok(IndexMatch {
+                                object: object_from_row(row, &url_base, delimiter)?,
+                                connection_id: row.get(6)?,
+                                bucket_name: row.get(7)?,
+                            })
+                        },
+                    )?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+
+                batch
+            };
+
+            let done = batch.len() < SEARCH_BATCH_SIZE;
+            after = batch.last().map(|last| {
+                (
+                    last.connection_id.clone(),
+                    last.bucket_name.clone(),
+                    last.object.key.clone(),
+                )
+            });
+
+            for found in batch {
+                if !visit(found) {
+                    return Ok(());
+                }
+            }
+
+            if done {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+const INDEXED_BUCKET_COLUMNS: &str =
+    "b.connection_id, b.bucket_name, b.indexed_at, b.object_count, b.total_size,
+    (SELECT COUNT(*) FROM index_dirty_prefixes d WHERE d.bucket_id = b.id), b.needs_full_crawl";
+
+fn indexed_bucket_from_row(row: &Row) -> rusqlite::Result<IndexedBucket> {
+    Ok(IndexedBucket {
+        connection_id: row.get(0)?,
+        bucket_name: row.get(1)?,
+        indexed_at: row.get(2)?,
+        object_count: row.get(3)?,
+        total_size: row.get(4)?,
+        pending_changes: row.get(5)?,
+        needs_full_crawl: row.get(6)?,
+    })
+}
+
+// The smallest string sorting after every key that starts with `prefix`,
+// None for the empty prefix which covers everything
+fn prefix_end(prefix: &str) -> Option<String> {
+    let mut chars: Vec<char> = prefix.chars().collect();
+
+    while let Some(last) = chars.pop() {
+        if let Some(next) = char::from_u32(last as u32 + 1) {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+
+    None
+}
+
+fn inventory_from_row(row: &Row) -> rusqlite::Result<InventoryInfo> {
+    Ok(InventoryInfo {
+        id: row.get(0)?,
+        connection_id: row.get(1)?,
+        bucket_name: row.get(2)?,
+        manifest_bucket: row.get(3)?,
+        manifest_key: row.get(4)?,
+        inventory_date: row.get(5)?,
+        imported_at: row.get(6)?,
+        object_count: row.get(7)?,
+        total_size: row.get(8)?,
+    })
+}
+
+fn object_from_row(row: &Row, url_base: &str, delimiter: &str) -> rusqlite::Result<ObjectInfo> {
+    let key: String = row.get(0)?;
+    let is_folder_marker = key.ends_with(delimiter);
+
+    Ok(ObjectInfo {
+        url: url::object_url(url_base, None, &key),
+        size: row.get(1)?,
+        last_modified: row.get(2)?,
+        storage_class: row.get(4)?,
+        is_folder: is_folder_marker,
+        kind: if is_folder_marker {
+            ObjectKind::FolderMarker
+        } else {
+            ObjectKind::File
+        },
+        etag: row.get(3)?,
+        checksum_algorithms: Vec::new(),
+        owner: None,
+        restore_status: None,
+        key,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_end_bounds_every_key_below_the_prefix() {
+        assert_eq!(prefix_end(""), None);
+        assert_eq!(prefix_end("photos/").as_deref(), Some("photos0"));
+        assert_eq!(prefix_end("abc").as_deref(), Some("abd"));
+        assert_eq!(prefix_end("caf\u{e9}").as_deref(), Some("caf\u{ea}"));
+        assert_eq!(prefix_end("a\u{10FFFF}").as_deref(), Some("b"));
+
+        let end = prefix_end("photos/").unwrap();
+        assert!("photos/\u{10FFFF}" < end.as_str());
+        assert!("photos0" >= end.as_str());
+    }
+
+    #[test]
+    fn a_bucket_is_crawled_once_at_a_time() {
+        let index = ObjectIndex::new(PathBuf::from("unused.sqlite3"));
+
+        let claim = index.claim_crawl(1);
+        assert!(claim.is_some());
+        assert!(index.claim_crawl(1).is_none());
+        assert!(index.claim_crawl(2).is_some());
+
+        drop(claim);
+        assert!(index.claim_crawl(1).is_some());
+    }
+
+    fn inventory_with_keys(keys: &[&str]) -> (ObjectIndex, i64) {
+        let index = ObjectIndex::new(PathBuf::from(":memory:"));
+        let inventory_id = index
+            .create_inventory(&NewInventory {
+                connection_id: "conn".to_string(),
+                bucket_name: "bucket".to_string(),
+                url_base: "https://bucket.example.com".to_string(),
+                manifest_bucket: "inventories".to_string(),
+                manifest_key: "bucket/manifest.json".to_string(),
+                inventory_date: "2024-03-01T00:00:00Z".to_string(),
+                imported_at: "2024-03-02T00:00:00Z".to_string(),
+            })
+            .unwrap();
+
+        for key in keys {
+            index
+                .lock()
+                .unwrap()
+                .execute(
+                    "INSERT INTO inventory_objects (inventory_id, key, size) VALUES (?1, ?2, 0)",
+                    params![inventory_id, key],
+                )
+                .unwrap();
+        }
+
+        (index, inventory_id)
+    }
+
+    #[test]
+    fn inventory_folders_follow_the_delimiter() {
+        let (index, inventory_id) =
+            inventory_with_keys(&["a/b", "logs:", "logs:2024:01", "logs:2024:02", "z"]);
+        let browse = |prefix: &str, start_after: Option<&str>| {
+            let (objects, _) = index
+                .browse_inventory(
+                    inventory_id,
+                    "https://bucket.example.com",
+                    prefix,
+                    ":",
+                    start_after,
+                    10,
+                )
+                .unwrap();
+
+            objects
+                .into_iter()
+                .map(|object| (object.key, object.kind))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            browse("", None),
+            [
+                ("a/b".to_string(), ObjectKind::File),
+                ("logs:".to_string(), ObjectKind::Prefix),
+                ("z".to_string(), ObjectKind::File),
+            ]
+        );
+        assert_eq!(
+            browse("", Some("logs:")),
+            [("z".to_string(), ObjectKind::File)]
+        );
+        assert_eq!(
+            browse("logs:", None),
+            [("logs:2024:".to_string(), ObjectKind::Prefix)]
+        );
+
+        let mut kinds = Vec::new();
+        index
+            .scan_inventory(
+                inventory_id,
+                "https://bucket.example.com",
+                "",
+                ":",
+                |object| {
+                    kinds.push(object.kind);
+                    true
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            kinds,
+            [
+                ObjectKind::File,
+                ObjectKind::FolderMarker,
+                ObjectKind::File,
+                ObjectKind::File,
+                ObjectKind::File,
+            ]
+        );
+    }
+
+    #[test]
+    fn pruning_drops_the_buckets_of_unknown_connections() {
+        let index = ObjectIndex::new(PathBuf::from(":memory:"));
+
+        index
+            .add_indexed_bucket("S3|AKIAKEPT", "photos", "https://photos.example.com")
+            .unwrap();
+        index
+            .add_indexed_bucket("S3|AKIAGONE", "photos", "https://photos.example.com")
+            .unwrap();
+
+        let pruned = index
+            .prune_indexed_buckets(&["S3|AKIAKEPT".to_string()])
+            .unwrap();
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].connection_id, "S3|AKIAGONE");
+
+        let remaining = index.list_indexed_buckets().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].connection_id, "S3|AKIAKEPT");
+    }
+
+    #[test]
+    fn inventory_scans_continue_across_batches() {
+        let keys: Vec<String> = (0..SEARCH_BATCH_SIZE + 5)
+            .map(|i| format!("logs/{:05}", i))
+            .chain(["a".to_string(), "logs0".to_string()])
+            .collect();
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let (index, inventory_id) = inventory_with_keys(&keys);
+
+        let mut scanned = Vec::new();
+        index
+            .scan_inventory(
+                inventory_id,
+                "https://bucket.example.com",
+                "logs/",
+                "/",
+                |object| {
+                    scanned.push(object.key);
+                    true
+                },
+            )
+            .unwrap();
+        assert_eq!(scanned.len(), SEARCH_BATCH_SIZE + 5);
+        assert_eq!(scanned.first().map(String::as_str), Some("logs/00000"));
+        assert_eq!(scanned.last().map(String::as_str), Some("logs/01004"));
+
+        let mut visited = 0;
+        index
+            .scan_inventory(
+                inventory_id,
+                "https://bucket.example.com",
+                "logs/",
+                "/",
+                |_| {
+                    visited += 1;
+                    visited < 3
+                },
+            )
+            .unwrap();
+        assert_eq!(visited, 3);
+    }
+}
@@ -0,0 +1,179 @@
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+use flate2::read::MultiGzDecoder;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
+use serde::Deserialize;
+use std::fs::File;
+use std::path::Path;
+
+use super::url;
+
+// The manifest.json written next to every S3 Inventory delivery
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryManifest {
+    pub source_bucket: String,
+    // Milliseconds since the epoch, as a string
+    pub creation_timestamp: String,
+    pub file_format: String,
+    pub file_schema: String,
+    pub files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ManifestFile {
+    pub key: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InventoryFormat {
+    Csv,
+    Parquet,
+}
+
+#[derive(Debug, Default)]
+pub struct InventoryRecord {
+    pub key: String,
+    pub size: Option<i64>,
+    pub last_modified: Option<String>,
+    pub etag: Option<String>,
+    pub storage_class: Option<String>,
+}
+
+impl InventoryManifest {
+    pub fn parse(contents: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice(contents).map_err(|e| format!("Invalid inventory manifest: {}", e))
+    }
+
+    pub fn format(&self) -> Result<InventoryFormat, String> {
+        match self.file_format.to_uppercase().as_str() {
+            "CSV" => Ok(InventoryFormat::Csv),
+            "PARQUET" => Ok(InventoryFormat::Parquet),
+            other => Err(format!("{} inventories are not supported", other)),
+        }
+    }
+
+    pub fn inventory_date(&self) -> Result<DateTime, String> {
+        self.creation_timestamp
+            .parse::<i64>()
+            .map(DateTime::from_millis)
+            .map_err(|e| format!("Invalid inventory creation timestamp: {}", e))
+    }
+
+    // CSV files have no header row, the column order comes from the schema
+    fn csv_column(&self, name: &str) -> Option<usize> {
+        self.file_schema
+            .split(',')
+            .position(|column| column.trim().eq_ignore_ascii_case(name))
+    }
+}
+
+// Inventories of versioned buckets list every version, only current objects
+// are passed on.
+pub fn read_records(
+    manifest: &InventoryManifest,
+    path: &Path,
+    on_record: impl FnMut(InventoryRecord) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match manifest.format()? {
+        InventoryFormat::Csv => read_csv(manifest, path, on_record),
+        InventoryFormat::Parquet => read_parquet(path, on_record),
+    }
+}
+
+fn read_csv(
+    manifest: &InventoryManifest,
+    path: &Path,
+    mut on_record: impl FnMut(InventoryRecord) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let key_column = manifest
+        .csv_column("Key")
+        .ok_or("Inventory schema has no Key column")?;
+    let size_column = manifest.csv_column("Size");
+    let last_modified_column = manifest.csv_column("LastModifiedDate");
+    let etag_column = manifest.csv_column("ETag");
+    let storage_class_column = manifest.csv_column("StorageClass");
+    let is_latest_column = manifest.csv_column("IsLatest");
+    let is_delete_marker_column = manifest.csv_column("IsDeleteMarker");
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(MultiGzDecoder::new(File::open(path)?));
+
+    for row in reader.records() {
+        let row = row?;
+        let value = |column: Option<usize>| {
+            column
+                .and_then(|index| row.get(index))
+                .filter(|value| !value.is_empty())
+        };
+
+        if value(is_latest_column) == Some("false")
+            || value(is_delete_marker_column) == Some("true")
+        {
+            continue;
+        }
+
+        // Keys are URL encoded in CSV inventories
+        let Some(key) = value(Some(key_column)).map(url::decode_key) else {
+            continue;
+        };
+
+        on_record(InventoryRecord {
+            key,
+            size: value(size_column).and_then(|size| size.parse().ok()),
+            last_modified: value(last_modified_column).map(|date| {
+                DateTime::from_str(date, DateTimeFormat::DateTime)
+                    .map(|date| date.to_string())
+                    .unwrap_or_else(|_| date.to_string())
+            }),
+            etag: value(etag_column).map(|etag| etag.trim_matches('"').to_string()),
+            storage_class: value(storage_class_column).map(|sc| sc.to_string()),
+        })?;
+    }
+
+    Ok(())
+}
+
+fn read_parquet(
+    path: &Path,
+    mut on_record: impl FnMut(InventoryRecord) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let reader = SerializedFileReader::new(File::open(path)?)?;
+
+    for row in reader.get_row_iter(None)? {
+        let row = row?;
+        let mut record = InventoryRecord::default();
+        let mut current = true;
+
+        for (name, field) in row.get_column_iter() {
+            match (name.as_str(), field) {
+                ("key", Field::Str(key)) => record.key = key.clone(),
+                ("size", Field::Long(size)) => record.size = Some(*size),
+                ("last_modified_date", Field::TimestampMillis(millis)) => {
+                    record.last_modified = Some(DateTime::from_millis(*millis).to_string())
+                }
+                ("last_modified_date", Field::TimestampMicros(micros)) => {
+                    record.last_modified = Some(DateTime::from_millis(*micros / 1000).to_string())
+                }
+                ("e_tag", Field::Str(etag)) => {
+                    record.etag = Some(etag.trim_matches('"').to_string())
+                }
+                ("storage_class", Field::Str(storage_class)) => {
+                    record.storage_class = Some(storage_class.clone())
+                }
+                ("is_latest", Field::Bool(false)) | ("is_delete_marker", Field::Bool(true)) => {
+                    current = false
+                }
+                _ => {}
+            }
+        }
+
+        if current && !record.key.is_empty() {
+            on_record(record)?;
+        }
+    }
+
+    Ok(())
+}
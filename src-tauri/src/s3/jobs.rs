@@ -0,0 +1,59 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::AppHandle;
+use tauri_specta::Event;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+pub type JobMap = Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>;
+
+/// Cancellation handle handed to a background job. Jobs are expected to check
+/// `is_cancelled` between pages and stop as soon as it flips.
+#[derive(Clone)]
+pub struct JobHandle {
+    pub id: String,
+    cancelled: Arc<AtomicBool>,
+    jobs: JobMap,
+}
+
+impl JobHandle {
+    pub async fn register(jobs: &JobMap) -> Self {
+        let id = Uuid::new_v4().to_string();
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        jobs.lock().await.insert(id.clone(), cancelled.clone());
+
+        JobHandle {
+            id,
+            cancelled,
+            jobs: jobs.clone(),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub async fn finish(self) {
+        self.jobs.lock().await.remove(&self.id);
+    }
+}
+
+pub fn emit<E: Event + Serialize + Clone>(app: &AppHandle<tauri::Wry>, event: E) {
+    if let Err(e) = event.emit(app) {
+        eprintln!("Failed to emit {}: {}", E::NAME, e);
+    }
+}
+
+pub async fn cancel(jobs: &JobMap, job_id: &str) -> Result<(), String> {
+    let jobs = jobs.lock().await;
+    let cancelled = jobs
+        .get(job_id)
+        .ok_or_else(|| "Job not found".to_string())?;
+
+    cancelled.store(true, Ordering::Relaxed);
+
+    Ok(())
+}
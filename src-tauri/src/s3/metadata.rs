@@ -0,0 +1,243 @@
+use aws_sdk_s3::operation::head_object::HeadObjectOutput;
+use aws_sdk_s3::types::{ChecksumAlgorithm, Grant, Permission};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub enum MetadataTarget {
+    Object(String),
+    Prefix(String),
+}
+
+// `None` keeps the current value of a header, an empty string removes it.
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct MetadataChanges {
+    pub content_type: Option<String>,
+    pub cache_control: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_language: Option<String>,
+    pub expires: Option<String>,
+    pub set_metadata: HashMap<String, String>,
+    pub remove_metadata: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, PartialEq)]
+pub struct ObjectHeaders {
+    pub content_type: Option<String>,
+    pub cache_control: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_language: Option<String>,
+    pub expires: Option<String>,
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct MetadataUpdate {
+    pub key: String,
+    pub before: Option<ObjectHeaders>,
+    pub after: Option<ObjectHeaders>,
+    pub error: Option<String>,
+}
+
+impl MetadataUpdate {
+    pub fn failed(key: String, error: String) -> Self {
+        MetadataUpdate {
+            key,
+            before: None,
+            after: None,
+            error: Some(error),
+        }
+    }
+}
+
+impl ObjectHeaders {
+    pub fn from_head(head: &HeadObjectOutput) -> Self {
+        ObjectHeaders {
+            content_type: head.content_type().map(|v| v.to_string()),
+            cache_control: head.cache_control().map(|v| v.to_string()),
+            content_disposition: head.content_disposition().map(|v| v.to_string()),
+            content_encoding: head.content_encoding().map(|v| v.to_string()),
+            content_language: head.content_language().map(|v| v.to_string()),
+            expires: head.expires_string().map(|v| v.to_string()),
+            metadata: head.metadata().cloned().unwrap_or_default(),
+        }
+    }
+
+    pub fn apply(&self, changes: &MetadataChanges) -> Self {
+        // S3 stores user metadata keys in lower case
+        let mut metadata = self.metadata.clone();
+
+        for key in &changes.remove_metadata {
+            metadata.remove(&key.to_lowercase());
+        }
+
+        for (key, value) in &changes.set_metadata {
+            metadata.insert(key.to_lowercase(), value.clone());
+        }
+
+        ObjectHeaders {
+            content_type: merge(&self.content_type, &changes.content_type),
+            cache_control: merge(&self.cache_control, &changes.cache_control),
+            content_disposition: merge(&self.content_disposition, &changes.content_disposition),
+            content_encoding: merge(&self.content_encoding, &changes.content_encoding),
+            content_language: merge(&self.content_language, &changes.content_language),
+            expires: merge(&self.expires, &changes.expires),
+            metadata,
+        }
+    }
+}
+
+fn merge(current: &Option<String>, change: &Option<String>) -> Option<String> {
+    match change {
+        Some(value) if value.is_empty() => None,
+        Some(value) => Some(value.clone()),
+        None => current.clone(),
+    }
+}
+
+// The algorithm of the checksum an object was uploaded with. HeadObject only
+// returns checksums when asked for them with ChecksumMode::Enabled.
+pub fn checksum_algorithm(head: &HeadObjectOutput) -> Option<ChecksumAlgorithm> {
+    [
+        (head.checksum_crc32(), ChecksumAlgorithm::Crc32),
+        (head.checksum_crc32_c(), ChecksumAlgorithm::Crc32C),
+        (head.checksum_crc64_nvme(), ChecksumAlgorithm::Crc64Nvme),
+        (head.checksum_sha1(), ChecksumAlgorithm::Sha1),
+        (head.checksum_sha256(), ChecksumAlgorithm::Sha256),
+    ]
+    .into_iter()
+    .find_map(|(value, algorithm)| value.map(|_| algorithm))
+}
+
+// Grant headers that give a copy the ACL of its source, each a comma separated
+// list of grantees like `id="..."` or `uri="..."`
+#[derive(Debug, Default, PartialEq)]
+pub struct AclGrants {
+    pub full_control: Option<String>,
+    pub read: Option<String>,
+    pub read_acp: Option<String>,
+    pub write_acp: Option<String>,
+}
+
+// None for the default ACL, which only gives the owner full control. Every
+// copy gets that one anyway, and buckets with ACLs disabled reject any other.
+pub fn acl_grants(owner_id: Option<&str>, grants: &[Grant]) -> Option<AclGrants> {
+    let is_default = grants.iter().all(|grant| {
+        grant.permission() == Some(&Permission::FullControl)
+            && grant
+                .grantee()
+                .is_some_and(|grantee| grantee.id().is_some() && grantee.id() == owner_id)
+    });
+
+    if is_default {
+        return None;
+    }
+
+    let mut headers = AclGrants::default();
+
+    for grant in grants {
+        let Some(grantee) = grant.grantee() else {
+            continue;
+        };
+
+        let grantee = match (grantee.id(), grantee.uri(), grantee.email_address()) {
+            (Some(id), _, _) => format!("id=\"{}\"", id),
+            (_, Some(uri), _) => format!("uri=\"{}\"", uri),
+            (_, _, Some(email)) => format!("emailAddress=\"{}\"", email),
+            _ => continue,
+        };
+
+        let header = match grant.permission() {
+            Some(Permission::FullControl) => &mut headers.full_control,
+            Some(Permission::Read) => &mut headers.read,
+            Some(Permission::ReadAcp) => &mut headers.read_acp,
+            Some(Permission::WriteAcp) => &mut headers.write_acp,
+            // WRITE has no meaning on objects
+            _ => continue,
+        };
+
+        match header {
+            Some(list) => {
+                list.push_str(", ");
+                list.push_str(&grantee);
+            }
+            None => *header = Some(grantee),
+        }
+    }
+
+    Some(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_s3::types::{Grantee, Type};
+
+    fn grant(grantee: Grantee, permission: Permission) -> Grant {
+        Grant::builder()
+            .grantee(grantee)
+            .permission(permission)
+            .build()
+    }
+
+    fn user(id: &str) -> Grantee {
+        Grantee::builder()
+            .id(id)
+            .r#type(Type::CanonicalUser)
+            .build()
+            .unwrap()
+    }
+
+    fn group(uri: &str) -> Grantee {
+        Grantee::builder()
+            .uri(uri)
+            .r#type(Type::Group)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn default_acl_needs_no_grant_headers() {
+        let grants = [grant(user("owner"), Permission::FullControl)];
+
+        assert_eq!(acl_grants(Some("owner"), &grants), None);
+        assert_eq!(acl_grants(Some("owner"), &[]), None);
+    }
+
+    #[test]
+    fn public_read_acl_is_carried_over() {
+        let all_users = "http://acs.amazonaws.com/groups/global/AllUsers";
+        let grants = [
+            grant(user("owner"), Permission::FullControl),
+            grant(group(all_users), Permission::Read),
+            grant(user("auditor"), Permission::Read),
+            grant(user("auditor"), Permission::ReadAcp),
+        ];
+
+        assert_eq!(
+            acl_grants(Some("owner"), &grants),
+            Some(AclGrants {
+                full_control: Some("id=\"owner\"".to_string()),
+                read: Some(format!("uri=\"{}\", id=\"auditor\"", all_users)),
+                read_acp: Some("id=\"auditor\"".to_string()),
+                write_acp: None,
+            })
+        );
+    }
+
+    #[test]
+    fn checksum_algorithm_follows_the_stored_checksum() {
+        let head = HeadObjectOutput::builder()
+            .checksum_sha256("n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=")
+            .build();
+
+        assert_eq!(checksum_algorithm(&head), Some(ChecksumAlgorithm::Sha256));
+        assert_eq!(
+            checksum_algorithm(&HeadObjectOutput::builder().build()),
+            None
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::mem;
+
+use super::versions::ObjectVersion;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+// A non-current version is purged when any of the set rules matches. The
+// current version of a key is never touched.
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct PurgeRules {
+    // Days since the version was replaced by a newer one
+    pub noncurrent_older_than_days: Option<u32>,
+    pub keep_noncurrent_versions: Option<u32>,
+    // Delete markers with no versions left underneath them
+    pub remove_orphaned_delete_markers: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone, Default)]
+pub struct PurgeSummary {
+    pub keys_affected: u64,
+    pub versions_deleted: u64,
+    pub delete_markers_deleted: u64,
+    pub bytes_reclaimed: i64,
+    pub errors: Vec<String>,
+}
+
+impl PurgeSummary {
+    // Counts versions that are actually gone. All planned versions of a key
+    // are taken in the same batch, so a key is never counted twice.
+    pub fn record(&mut self, deleted: &[ObjectVersion]) {
+        let mut previous_key = None;
+
+        for version in deleted {
+            if previous_key != Some(&version.key) {
+                self.keys_affected += 1;
+                previous_key = Some(&version.key);
+            }
+
+            if version.is_delete_marker {
+                self.delete_markers_deleted += 1;
+            } else {
+                self.versions_deleted += 1;
+                self.bytes_reclaimed += version.size.unwrap_or(0);
+            }
+        }
+    }
+}
+
+// Groups versions by key as they come out of the listing (by key, newest
+// first) and decides which of them go.
+pub struct PurgePlanner {
+    rules: PurgeRules,
+    now: DateTime,
+    key_versions: Vec<ObjectVersion>,
+    pending: Vec<ObjectVersion>,
+}
+
+impl PurgePlanner {
+    pub fn new(rules: PurgeRules, now: DateTime) -> Self {
+        PurgePlanner {
+            rules,
+            now,
+            key_versions: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, version: ObjectVersion) {
+        if self
+            .key_versions
+            .first()
+            .is_some_and(|first| first.key != version.key)
+        {
+            self.plan_key();
+        }
+
+        self.key_versions.push(version);
+    }
+
+    pub fn finish(&mut self) {
+        self.plan_key();
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn take_pending(&mut self) -> Vec<ObjectVersion> {
+        mem::take(&mut self.pending)
+    }
+
+    fn plan_key(&mut self) {
+        let versions = mem::take(&mut self.key_versions);
+        let mut planned = Vec::new();
+        let mut kept_versions = 0;
+        let mut noncurrent_versions = 0;
+        let mut replaced_at: Option<DateTime> = None;
+        let mut latest_marker = None;
+
+        for version in versions {
+            let last_modified = version
+                .last_modified
+                .as_deref()
+                .and_then(|date| DateTime::from_str(date, DateTimeFormat::DateTime).ok());
+
+            if version.is_latest {
+                replaced_at = last_modified;
+
+                if version.is_delete_marker {
+                    latest_marker = Some(version);
+                } else {
+                    kept_versions += 1;
+                }
+
+                continue;
+            }
+
+            let noncurrent_days =
+                replaced_at.map(|replaced| (self.now.secs() - replaced.secs()) / SECONDS_PER_DAY);
+            // Only real versions count toward the ones to keep, a noncurrent
+            // delete marker goes once that many newer versions are kept
+            let purge = self
+                .rules
+                .keep_noncurrent_versions
+                .is_some_and(|keep| noncurrent_versions >= keep)
+                || self
+                    .rules
+                    .noncurrent_older_than_days
+                    .is_some_and(|days| noncurrent_days.is_some_and(|age| age >= days as i64));
+
+            replaced_at = last_modified;
+
+            if !version.is_delete_marker {
+                noncurrent_versions += 1;
+            }
+
+            if purge {
+                planned.push(version);
+            } else if !version.is_delete_marker {
+                kept_versions += 1;
+            }
+        }
+
+        if let Some(marker) = latest_marker {
+            if self.rules.remove_orphaned_delete_markers && kept_versions == 0 {
+                planned.push(marker);
+            }
+        }
+
+        self.pending.extend(planned);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::test_support;
+
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    // Versions are placed on whole days since the epoch
+    fn version(key: &str, id: &str, day: i64, is_latest: bool) -> ObjectVersion {
+        test_support::version(key, id, &date(day), is_latest)
+    }
+
+    fn delete_marker(key: &str, id: &str, day: i64, is_latest: bool) -> ObjectVersion {
+        test_support::delete_marker(key, id, &date(day), is_latest)
+    }
+
+    fn date(day: i64) -> String {
+        DateTime::from_secs(day * DAY)
+            .fmt(DateTimeFormat::DateTime)
+            .unwrap()
+    }
+
+    fn rules() -> PurgeRules {
+        PurgeRules {
+            noncurrent_older_than_days: None,
+            keep_noncurrent_versions: None,
+            remove_orphaned_delete_markers: false,
+        }
+    }
+
+    fn plan(rules: PurgeRules, today: i64, versions: Vec<ObjectVersion>) -> Vec<String> {
+        let mut planner = PurgePlanner::new(rules, DateTime::from_secs(today * DAY));
+
+        for version in versions {
+            planner.push(version);
+        }
+
+        planner.finish();
+        planner
+            .take_pending()
+            .into_iter()
+            .map(|version| version.version_id)
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_newest_noncurrent_versions() {
+        let rules = PurgeRules {
+            keep_noncurrent_versions: Some(1),
+            ..rules()
+        };
+        let planned = plan(
+            rules,
+            100,
+            vec![
+                version("a", "a4", 40, true),
+                version("a", "a3", 30, false),
+                version("a", "a2", 20, false),
+                version("a", "a1", 10, false),
+                version("b", "b2", 20, true),
+                version("b", "b1", 10, false),
+            ],
+        );
+
+        assert_eq!(planned, vec!["a2", "a1"]);
+    }
+
+    #[test]
+    fn age_counts_from_when_a_version_was_replaced() {
+        let rules = PurgeRules {
+            noncurrent_older_than_days: Some(30),
+            ..rules()
+        };
+        // a2 was replaced on day 80 and a1 on day 50
+        let planned = plan(
+            rules,
+            100,
+            vec![
+                version("a", "a3", 80, true),
+                version("a", "a2", 50, false),
+                version("a", "a1", 10, false),
+            ],
+        );
+
+        assert_eq!(planned, vec!["a1"]);
+    }
+
+    #[test]
+    fn current_versions_are_never_purged() {
+        let rules = PurgeRules {
+            noncurrent_older_than_days: Some(0),
+            keep_noncurrent_versions: Some(0),
+            remove_orphaned_delete_markers: true,
+        };
+        let planned = plan(rules, 100, vec![version("a", "a1", 10, true)]);
+
+        assert!(planned.is_empty());
+    }
+
+    #[test]
+    fn removes_delete_markers_left_without_versions() {
+        let rules = PurgeRules {
+            keep_noncurrent_versions: Some(0),
+            remove_orphaned_delete_markers: true,
+            ..rules()
+        };
+        let planned = plan(
+            rules,
+            100,
+            vec![
+                delete_marker("a", "a2", 20, true),
+                version("a", "a1", 10, false),
+            ],
+        );
+
+        assert_eq!(planned, vec!["a1", "a2"]);
+    }
+
+    #[test]
+    fn keeps_delete_markers_over_kept_versions() {
+        let rules = PurgeRules {
+            remove_orphaned_delete_markers: true,
+            ..rules()
+        };
+        let planned = plan(
+            rules,
+            100,
+            vec![
+                delete_marker("a", "a2", 20, true),
+                version("a", "a1", 10, false),
+            ],
+        );
+
+        assert!(planned.is_empty());
+    }
+
+    #[test]
+    fn summary_counts_keys_versions_and_markers() {
+        let mut summary = PurgeSummary::default();
+
+        summary.record(&[
+            version("a", "a2", 20, false),
+            version("a", "a1", 10, false),
+            delete_marker("b", "b1", 10, true),
+        ]);
+        summary.record(&[version("c", "c1", 10, false)]);
+
+        assert_eq!(summary.keys_affected, 3);
+        assert_eq!(summary.versions_deleted, 3);
+        assert_eq!(summary.delete_markers_deleted, 1);
+        assert_eq!(summary.bytes_reclaimed, 30);
+    }
+
+    #[test]
+    fn delete_markers_do_not_count_toward_kept_versions() {
+        let rules = PurgeRules {
+            keep_noncurrent_versions: Some(2),
+            ..rules()
+        };
+        let planned = plan(
+            rules,
+            100,
+            vec![
+                version("a", "a6", 60, true),
+                delete_marker("a", "a5", 50, false),
+                version("a", "a4", 40, false),
+                delete_marker("a", "a3", 30, false),
+                version("a", "a2", 20, false),
+                delete_marker("a", "a1", 10, false),
+                version("a", "a0", 5, false),
+            ],
+        );
+
+        assert_eq!(planned, vec!["a1", "a0"]);
+    }
+}
@@ -0,0 +1,82 @@
+use aws_sdk_s3::types::BucketLocationConstraint;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub const DEFAULT_REGION: &str = "us-east-1";
+
+// Detected bucket regions by connection and bucket. A bucket can't move to
+// another region, so entries never expire.
+#[derive(Clone, Default)]
+pub struct BucketRegionCache {
+    inner: Arc<Mutex<HashMap<(String, String), String>>>,
+}
+
+impl BucketRegionCache {
+    pub async fn get(&self, connection_id: &str, bucket_name: &str) -> Option<String> {
+        self.inner
+            .lock()
+            .await
+            .get(&(connection_id.to_string(), bucket_name.to_string()))
+            .cloned()
+    }
+
+    pub async fn insert(&self, connection_id: &str, bucket_name: &str, region: String) {
+        self.inner
+            .lock()
+            .await
+            .insert((connection_id.to_string(), bucket_name.to_string()), region);
+    }
+
+    // A deleted bucket name can be taken again in another region
+    pub async fn remove(&self, connection_id: &str, bucket_name: &str) {
+        self.inner
+            .lock()
+            .await
+            .remove(&(connection_id.to_string(), bucket_name.to_string()));
+    }
+}
+
+// The frontend passes an empty string for buckets whose region is unknown
+pub fn known_region(region: Option<&str>) -> Option<&str> {
+    region.filter(|region| !region.is_empty())
+}
+
+// GetBucketLocation answers with no constraint for us-east-1 and with the
+// legacy `EU` for eu-west-1.
+pub fn region_from_location(location: Option<&BucketLocationConstraint>) -> String {
+    match location.map(|location| location.as_str()) {
+        None | Some("") => DEFAULT_REGION.to_string(),
+        Some("EU") => "eu-west-1".to_string(),
+        Some(region) => region.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_from_location_handles_legacy_constraints() {
+        assert_eq!(region_from_location(None), "us-east-1");
+        assert_eq!(
+            region_from_location(Some(&BucketLocationConstraint::from(""))),
+            "us-east-1"
+        );
+        assert_eq!(
+            region_from_location(Some(&BucketLocationConstraint::Eu)),
+            "eu-west-1"
+        );
+        assert_eq!(
+            region_from_location(Some(&BucketLocationConstraint::EuCentral1)),
+            "eu-central-1"
+        );
+    }
+
+    #[test]
+    fn empty_region_is_unknown() {
+        assert_eq!(known_region(None), None);
+        assert_eq!(known_region(Some("")), None);
+        assert_eq!(known_region(Some("eu-west-1")), Some("eu-west-1"));
+    }
+}
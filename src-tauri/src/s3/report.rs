@@ -0,0 +1,281 @@
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BinaryHeap};
+
+use super::usage::{StorageClassUsage, UsageTotals};
+use super::{ObjectInfo, ObjectKind};
+
+pub const DEFAULT_TOP_N: u32 = 20;
+
+const KIB: i64 = 1024;
+const MIB: i64 = 1024 * KIB;
+const GIB: i64 = 1024 * MIB;
+
+const SIZE_BUCKETS: [(&str, i64); 5] = [
+    ("< 1 KiB", KIB),
+    ("1 KiB - 1 MiB", MIB),
+    ("1 MiB - 100 MiB", 100 * MIB),
+    ("100 MiB - 1 GiB", GIB),
+    (">= 1 GiB", i64::MAX),
+];
+
+// Matches the usual lifecycle transition thresholds
+const AGE_BUCKETS: [(&str, i64); 6] = [
+    ("< 30 days", 30),
+    ("30 - 90 days", 90),
+    ("90 - 180 days", 180),
+    ("180 - 365 days", 365),
+    ("1 - 2 years", 730),
+    (">= 2 years", i64::MAX),
+];
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct HistogramBucket {
+    pub label: String,
+    pub object_count: u64,
+    pub total_size: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct PrefixUsage {
+    pub prefix: String,
+    pub object_count: u64,
+    pub total_size: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct BucketReport {
+    pub bucket_name: String,
+    pub prefix: String,
+    pub generated_at: String,
+    pub total_size: i64,
+    pub object_count: u64,
+    pub storage_classes: Vec<StorageClassUsage>,
+    pub largest_objects: Vec<ObjectInfo>,
+    pub size_histogram: Vec<HistogramBucket>,
+    pub age_histogram: Vec<HistogramBucket>,
+    pub top_level_prefixes: Vec<PrefixUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub enum ReportFormat {
+    Json,
+    Csv,
+}
+
+// Orders objects by size so the heap can keep only the N largest
+struct RankedObject(ObjectInfo);
+
+impl RankedObject {
+    fn rank(&self) -> (i64, &str) {
+        (self.0.size.unwrap_or(0), &self.0.key)
+    }
+}
+
+impl PartialEq for RankedObject {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank() == other.rank()
+    }
+}
+
+impl Eq for RankedObject {}
+
+impl PartialOrd for RankedObject {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RankedObject {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+pub struct ReportBuilder {
+    prefix: String,
+    top_n: usize,
+    now: DateTime,
+    totals: UsageTotals,
+    largest: BinaryHeap<Reverse<RankedObject>>,
+    size_histogram: Vec<HistogramBucket>,
+    age_histogram: Vec<HistogramBucket>,
+    prefixes: BTreeMap<String, PrefixUsage>,
+}
+
+impl ReportBuilder {
+    pub fn new(prefix: String, top_n: u32, now: DateTime) -> Self {
+        ReportBuilder {
+            prefix,
+            top_n: top_n as usize,
+            now,
+            totals: UsageTotals::default(),
+            largest: BinaryHeap::new(),
+            size_histogram: empty_histogram(&SIZE_BUCKETS),
+            age_histogram: empty_histogram(&AGE_BUCKETS),
+            prefixes: BTreeMap::new(),
+        }
+    }
+
+    pub fn totals(&self) -> &UsageTotals {
+        &self.totals
+    }
+
+    pub fn add(&mut self, object: &ObjectInfo) {
+        if object.kind == ObjectKind::Prefix {
+            return;
+        }
+
+        let size = object.size.unwrap_or(0);
+
+        self.totals.add(object);
+
+        let size_index = SIZE_BUCKETS
+            .iter()
+            .position(|(_, upper)| size < *upper)
+            .unwrap_or(SIZE_BUCKETS.len() - 1);
+        record(&mut self.size_histogram[size_index], size);
+
+        if let Some(last_modified) = object
+            .last_modified
+            .as_deref()
+            .and_then(|d| DateTime::from_str(d, DateTimeFormat::DateTime).ok())
+        {
+            let age_days = (self.now.secs() - last_modified.secs()) / SECONDS_PER_DAY;
+            let age_index = AGE_BUCKETS
+                .iter()
+                .position(|(_, upper)| age_days < *upper)
+                .unwrap_or(AGE_BUCKETS.len() - 1);
+            record(&mut self.age_histogram[age_index], size);
+        }
+
+        let relative_key = object.key.strip_prefix(&self.prefix).unwrap_or(&object.key);
+        // Objects directly under the scanned prefix are grouped under the prefix itself
+        let top_level = match relative_key.split_once('/') {
+            Some((folder, _)) => format!("{}{}/", self.prefix, folder),
+            None => self.prefix.clone(),
+        };
+        let usage = self
+            .prefixes
+            .entry(top_level.clone())
+            .or_insert_with(|| PrefixUsage {
+                prefix: top_level,
+                object_count: 0,
+                total_size: 0,
+            });
+        usage.object_count += 1;
+        usage.total_size += size;
+
+        if self.top_n > 0 {
+            self.largest.push(Reverse(RankedObject(object.clone())));
+
+            if self.largest.len() > self.top_n {
+                self.largest.pop();
+            }
+        }
+    }
+
+    pub fn finish(self, bucket_name: String) -> BucketReport {
+        let mut largest_objects: Vec<ObjectInfo> = self
+            .largest
+            .into_iter()
+            .map(|Reverse(RankedObject(object))| object)
+            .collect();
+        largest_objects.sort_by_key(|object| Reverse(object.size));
+
+        let mut top_level_prefixes: Vec<PrefixUsage> = self.prefixes.into_values().collect();
+        top_level_prefixes.sort_by_key(|usage| Reverse(usage.total_size));
+
+        BucketReport {
+            bucket_name,
+            prefix: self.prefix,
+            generated_at: self.now.to_string(),
+            total_size: self.totals.total_size,
+            object_count: self.totals.object_count,
+            storage_classes: self.totals.storage_classes(),
+            largest_objects,
+            size_histogram: self.size_histogram,
+            age_histogram: self.age_histogram,
+            top_level_prefixes,
+        }
+    }
+}
+
+fn empty_histogram(buckets: &[(&str, i64)]) -> Vec<HistogramBucket> {
+    buckets
+        .iter()
+        .map(|(label, _)| HistogramBucket {
+            label: label.to_string(),
+            object_count: 0,
+            total_size: 0,
+        })
+        .collect()
+}
+
+fn record(bucket: &mut HistogramBucket, size: i64) {
+    bucket.object_count += 1;
+    bucket.total_size += size;
+}
+
+// Flattens every section of the report into one table so it opens cleanly in
+// a spreadsheet: section, name, object_count, total_size.
+pub fn report_to_csv(
+    report: &BucketReport,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer.write_record(["section", "name", "object_count", "total_size"])?;
+    writer.write_record([
+        "summary",
+        "total",
+        report.object_count.to_string().as_str(),
+        report.total_size.to_string().as_str(),
+    ])?;
+
+    for usage in &report.storage_classes {
+        writer.write_record([
+            "storage_class",
+            usage.storage_class.as_str(),
+            usage.object_count.to_string().as_str(),
+            usage.total_size.to_string().as_str(),
+        ])?;
+    }
+
+    for (section, histogram) in [
+        ("size_histogram", &report.size_histogram),
+        ("age_histogram", &report.age_histogram),
+    ] {
+        for bucket in histogram {
+            writer.write_record([
+                section,
+                bucket.label.as_str(),
+                bucket.object_count.to_string().as_str(),
+                bucket.total_size.to_string().as_str(),
+            ])?;
+        }
+    }
+
+    for usage in &report.top_level_prefixes {
+        writer.write_record([
+            "top_level_prefix",
+            usage.prefix.as_str(),
+            usage.object_count.to_string().as_str(),
+            usage.total_size.to_string().as_str(),
+        ])?;
+    }
+
+    for object in &report.largest_objects {
+        writer.write_record([
+            "largest_object",
+            object.key.as_str(),
+            "1",
+            object.size.unwrap_or(0).to_string().as_str(),
+        ])?;
+    }
+
+    Ok(writer.into_inner().map_err(|e| e.into_error())?)
+}
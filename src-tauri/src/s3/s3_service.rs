@@ -1,20 +1,53 @@
 use crate::s3::{BucketProvider, S3Config};
 
-use super::{BucketInfo, ObjectInfo};
+use super::metadata::{self, MetadataChanges, MetadataUpdate, ObjectHeaders};
+use super::presign::{
+    put_curl_command, sign_post_policy, PostPolicy, PresignedPost, PresignedUpload, PresignedUrl,
+    ResponseOverrides, SigningCredentials,
+};
+use super::region;
+use super::url;
+use super::versions::{ObjectVersion, VersionPage};
+use super::{
+    BucketInfo, ObjectChecksum, ObjectDetails, ObjectInfo, ObjectKind, ObjectOwner, ObjectTag,
+    RestoreStatus,
+};
 use aws_config::Region;
-use aws_sdk_s3::primitives::ByteStream;
-use aws_sdk_s3::types::{Delete, ObjectIdentifier};
+use aws_sdk_s3::config::Credentials;
+use aws_sdk_s3::error::ProvideErrorMetadata;
+use aws_sdk_s3::presigning::PresigningConfig;
+use aws_sdk_s3::primitives::{ByteStream, DateTime, DateTimeFormat};
+use aws_sdk_s3::types::{
+    BucketLocationConstraint, BucketVersioningStatus, ChecksumMode, CompletedMultipartUpload,
+    CompletedPart, CreateBucketConfiguration, Delete, EncodingType, MetadataDirective, Object,
+    ObjectIdentifier, OptionalObjectAttributes, ServerSideEncryption, VersioningConfiguration,
+};
 use aws_sdk_s3::{Client, Error};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use specta::Type;
 use std::io::{Cursor, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use tokio::fs::File;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::task::JoinHandle;
 use zip::write::ZipWriter;
 use zip::CompressionMethod;
 
+pub const DEFAULT_DELIMITER: &str = "/";
+
+// CopyObject only handles objects up to 5 GiB in a single request
+const MAX_COPY_OBJECT_SIZE: i64 = 5 * 1024 * 1024 * 1024;
+
+// Same limit for a single PutObject, larger bodies go through a multipart upload
+const MAX_PUT_OBJECT_SIZE: i64 = 5 * 1024 * 1024 * 1024;
+const MIN_PART_SIZE: u64 = 64 * 1024 * 1024;
+const MAX_PART_COUNT: u64 = 10_000;
+
+// Regions R2 accepts as a location hint when creating a bucket
+const R2_LOCATION_HINTS: [&str; 6] = ["wnam", "enam", "weur", "eeur", "apac", "oc"];
+
 type DownloadTaskHandle =
     JoinHandle<Result<(String, Vec<u8>), Box<dyn std::error::Error + Send + Sync>>>;
 
@@ -23,6 +56,9 @@ pub struct S3Service {
     client: Client,
     provider: BucketProvider,
     endpoint_url: String,
+    public_base_url: Option<String>,
+    region: String,
+    credentials: Credentials,
 }
 
 #[derive(Debug, Serialize, Deserialize, Type, Clone)]
@@ -33,15 +69,182 @@ pub struct S3ServiceConfig {
     pub provider: BucketProvider,
 }
 
+pub struct ObjectPage {
+    pub objects: Vec<ObjectInfo>,
+    pub next_continuation_token: Option<String>,
+}
+
+// Walks a listing one ListObjectsV2 page at a time so long-running jobs can
+// report progress and stop between requests.
+pub struct ObjectPager {
+    service: S3Service,
+    bucket_name: String,
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    folder_delimiter: String,
+    region: Option<String>,
+    continuation_token: Option<String>,
+    done: bool,
+}
+
+impl ObjectPager {
+    pub async fn next_page(&mut self) -> Result<Option<Vec<ObjectInfo>>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let page = self
+            .service
+            .list_objects_page(
+                &self.bucket_name,
+                self.prefix.as_deref(),
+                self.delimiter.as_deref(),
+                &self.folder_delimiter,
+                self.region.clone(),
+                self.continuation_token.take(),
+            )
+            .await?;
+
+        self.continuation_token = page.next_continuation_token;
+        self.done = self.continuation_token.is_none();
+
+        Ok(Some(page.objects))
+    }
+
+    // Flat listings send no delimiter, but keys ending with the one the
+    // folders are browsed with are still folder markers
+    pub fn folder_delimiter(mut self, delimiter: &str) -> Self {
+        self.folder_delimiter = delimiter.to_string();
+        self
+    }
+}
+
+// Same as ObjectPager, for ListObjectVersions
+pub struct VersionPager {
+    service: S3Service,
+    bucket_name: String,
+    prefix: Option<String>,
+    key_marker: Option<String>,
+    version_id_marker: Option<String>,
+    done: bool,
+}
+
+impl VersionPager {
+    pub async fn next_page(&mut self) -> Result<Option<Vec<ObjectVersion>>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let page = self
+            .service
+            .list_object_versions_page(
+                &self.bucket_name,
+                self.prefix.as_deref(),
+                self.key_marker.take(),
+                self.version_id_marker.take(),
+            )
+            .await?;
+
+        self.key_marker = page.next_key_marker;
+        self.version_id_marker = page.next_version_id_marker;
+        self.done = self.key_marker.is_none();
+
+        Ok(Some(page.versions))
+    }
+}
+
+// An upload that was started but never completed or aborted
+pub struct PendingUpload {
+    pub key: String,
+    pub upload_id: String,
+}
+
+// A key or version DeleteObjects refused to delete
+pub struct FailedDelete {
+    pub key: String,
+    pub version_id: Option<String>,
+    pub message: String,
+}
+
+impl std::fmt::Display for FailedDelete {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.version_id {
+            Some(version_id) => write!(f, "{} ({}): {}", self.key, version_id, self.message),
+            None => write!(f, "{}: {}", self.key, self.message),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct GetBucketEndpointOptions {
     name: String,
     region: Option<String>,
 }
 
+pub fn upload_key(prefix: Option<&str>, path: &Path) -> Option<String> {
+    let file_name = path.file_name().and_then(|name| name.to_str())?;
+
+    let key = match prefix {
+        Some(p) if !p.is_empty() => {
+            if p.ends_with('/') {
+                format!("{}{}", p, file_name)
+            } else {
+                format!("{}/{}", p, file_name)
+            }
+        }
+        _ => file_name.to_string(),
+    };
+
+    Some(key)
+}
+
+pub fn move_destination_key(key: &str, destination_prefix: &str) -> String {
+    let filename = key.rsplit('/').next().unwrap_or(key);
+
+    if destination_prefix.is_empty() {
+        filename.to_string()
+    } else if destination_prefix.ends_with('/') {
+        format!("{}{}", destination_prefix, filename)
+    } else {
+        format!("{}/{}", destination_prefix, filename)
+    }
+}
+
+// Parses the `x-amz-restore` header, e.g.
+// `ongoing-request="false", expiry-date="Fri, 21 Dec 2012 00:00:00 GMT"`
+fn parse_restore_header(header: &str) -> RestoreStatus {
+    let restore_expiry_date = header
+        .split_once("expiry-date=\"")
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .map(|(date, _)| date.to_string());
+
+    RestoreStatus {
+        is_restore_in_progress: header.contains("ongoing-request=\"true\""),
+        restore_expiry_date,
+    }
+}
+
+// XML 1.0 can't carry most control characters, not even as character
+// references, so DeleteObjects can't name keys that contain them
+fn is_xml_safe(key: &str) -> bool {
+    key.chars().all(|c| {
+        matches!(
+            c,
+            '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..
+        )
+    })
+}
+
+// R2 and some other S3 compatible stores answer features they don't implement
+// with one of these codes instead of an empty result
+fn is_unsupported_feature(code: Option<&str>) -> bool {
+    matches!(code, Some("NotImplemented" | "MethodNotAllowed"))
+}
+
 impl S3Service {
     pub async fn new(service_config: S3ServiceConfig) -> Result<Self, Error> {
-        let region = Region::new(service_config.region);
+        let region = Region::new(service_config.region.clone());
+        let public_base_url = service_config.config.common.public_base_url.clone();
 
         let aws_config = aws_config::ConfigLoader::default()
             .region(region)
@@ -49,7 +252,7 @@ impl S3Service {
             .load()
             .await;
 
-        let credentials = aws_sdk_s3::config::Credentials::new(
+        let credentials = Credentials::new(
             service_config.config.common.access_key_id,
             service_config.config.common.secret_access_key,
             None,
@@ -58,7 +261,7 @@ impl S3Service {
         );
 
         let s3_config = aws_sdk_s3::config::Builder::from(&aws_config)
-            .credentials_provider(credentials)
+            .credentials_provider(credentials.clone())
             .force_path_style(true) // Required for LocalStack
             .build();
 
@@ -69,21 +272,65 @@ impl S3Service {
             client,
             provider,
             endpoint_url: service_config.endpoint_url,
+            public_base_url,
+            region: service_config.region,
+            credentials,
         })
     }
 
+    // Without a known region the bucket is in the region this client talks to
     pub fn get_bucket_endpoint(&self, opts: GetBucketEndpointOptions) -> String {
-        let endpoint_url = match self.provider {
-            BucketProvider::S3 => {
-                format!(
-                    "https://s3.{}.amazonaws.com",
-                    opts.region.unwrap_or("us-east-1".to_string())
-                )
+        url::bucket_url(
+            &self.provider,
+            &self.endpoint_url,
+            &opts.name,
+            Some(region::known_region(opts.region.as_deref()).unwrap_or(&self.region)),
+        )
+    }
+
+    // HeadBucket is answered with the bucket's region from any regional
+    // endpoint, even as part of a redirect. GetBucketLocation covers the
+    // credentials that may only read the bucket location.
+    pub async fn detect_bucket_region(&self, bucket_name: &str) -> Result<String, Error> {
+        match self.client.head_bucket().bucket(bucket_name).send().await {
+            Ok(resp) => {
+                if let Some(region) = resp.bucket_region {
+                    return Ok(region);
+                }
             }
-            _ => self.endpoint_url.clone(),
-        };
+            Err(e) => {
+                let header = e
+                    .raw_response()
+                    .and_then(|resp| resp.headers().get("x-amz-bucket-region"));
 
-        format!("{}/{}", endpoint_url, opts.name)
+                if let Some(region) = header {
+                    return Ok(region.to_string());
+                }
+            }
+        }
+
+        let resp = self
+            .client
+            .get_bucket_location()
+            .bucket(bucket_name)
+            .send()
+            .await?;
+
+        Ok(region::region_from_location(resp.location_constraint()))
+    }
+
+    // Base that object URLs of the bucket are built from, the public base URL
+    // when one is configured
+    pub fn url_base(&self, bucket_name: &str, region: Option<String>) -> String {
+        let bucket_url = self.get_bucket_endpoint(GetBucketEndpointOptions {
+            name: bucket_name.to_string(),
+            region,
+        });
+
+        self.public_base_url
+            .clone()
+            .filter(|base| !base.is_empty())
+            .unwrap_or(bucket_url)
     }
 
     pub fn get_object_url(
@@ -99,16 +346,7 @@ impl S3Service {
 
         let bucket_url = self.get_bucket_endpoint(bucket_endpoint_opts);
 
-        // URL encode the object key to handle special characters, spaces, etc.
-        let encoded_key = object_key
-            .chars()
-            .map(|c| match c {
-                'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '_' | '.' | '~' | '/' => c.to_string(),
-                _ => format!("%{:02X}", c as u8),
-            })
-            .collect::<String>();
-
-        format!("{}/{}", bucket_url, encoded_key)
+        url::object_url(&bucket_url, self.public_base_url.as_deref(), object_key)
     }
 
     pub async fn list_buckets(&self) -> Result<Vec<BucketInfo>, Error> {
@@ -158,71 +396,601 @@ impl S3Service {
         Ok(all_buckets)
     }
 
+    // `region` is the AWS region, the R2 location hint or the region a MinIO
+    // server is configured with. us-east-1 is the default location and must
+    // not be sent as a constraint.
+    pub async fn create_bucket(
+        &self,
+        bucket_name: &str,
+        region: Option<&str>,
+        object_lock: bool,
+        versioning: bool,
+        policy: Option<&str>,
+    ) -> Result<BucketInfo, Box<dyn std::error::Error + Send + Sync>> {
+        if self.provider == BucketProvider::R2 {
+            if object_lock || versioning || policy.is_some() {
+                return Err(
+                    "R2 doesn't support object lock, versioning or bucket policies through the S3 API"
+                        .into(),
+                );
+            }
+
+            if let Some(hint) = region.filter(|hint| !R2_LOCATION_HINTS.contains(hint)) {
+                return Err(format!(
+                    "Unknown R2 location hint {}, expected one of {}",
+                    hint,
+                    R2_LOCATION_HINTS.join(", ")
+                )
+                .into());
+            }
+        }
+
+        let location = region.filter(|region| !region.is_empty() && *region != "us-east-1");
+        let mut request = self.client.create_bucket().bucket(bucket_name);
+
+        if let Some(location) = location {
+            request = request.create_bucket_configuration(
+                CreateBucketConfiguration::builder()
+                    .location_constraint(BucketLocationConstraint::from(location))
+                    .build(),
+            );
+        }
+
+        // Object lock can only be enabled at creation and turns versioning on
+        if object_lock {
+            request = request.object_lock_enabled_for_bucket(true);
+        }
+
+        request.send().await?;
+
+        if versioning && !object_lock {
+            self.client
+                .put_bucket_versioning()
+                .bucket(bucket_name)
+                .versioning_configuration(
+                    VersioningConfiguration::builder()
+                        .status(BucketVersioningStatus::Enabled)
+                        .build(),
+                )
+                .send()
+                .await
+                .map_err(|e| {
+                    format!(
+                        "Bucket {} was created, but enabling versioning failed: {}",
+                        bucket_name,
+                        Error::from(e)
+                    )
+                })?;
+        }
+
+        if let Some(policy) = policy {
+            self.client
+                .put_bucket_policy()
+                .bucket(bucket_name)
+                .policy(policy)
+                .send()
+                .await
+                .map_err(|e| {
+                    format!(
+                        "Bucket {} was created, but applying the policy failed: {}",
+                        bucket_name,
+                        Error::from(e)
+                    )
+                })?;
+        }
+
+        let region = match self.provider {
+            BucketProvider::S3 => self.region.clone(),
+            _ => region.unwrap_or_default().to_string(),
+        };
+
+        Ok(BucketInfo {
+            provider: self.provider.clone(),
+            name: bucket_name.to_string(),
+            endpoint_url: self.get_bucket_endpoint(GetBucketEndpointOptions {
+                name: bucket_name.to_string(),
+                region: Some(region.clone()),
+            }),
+            region,
+            creation_date: Some(DateTime::from(SystemTime::now()).to_string()),
+        })
+    }
+
+    pub async fn delete_bucket(
+        &self,
+        bucket_name: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match self.client.delete_bucket().bucket(bucket_name).send().await {
+            Ok(_) => Ok(()),
+            Err(e) if e.as_service_error().and_then(|e| e.code()) == Some("BucketNotEmpty") => {
+                Err(format!("Bucket {} is not empty", bucket_name).into())
+            }
+            Err(e) => Err(Error::from(e).into()),
+        }
+    }
+
+    // Suspended versioning still keeps the versions written while it was on.
+    // Providers without versioning support don't implement the call either.
+    pub async fn is_versioned(&self, bucket_name: &str) -> Result<bool, Error> {
+        match self
+            .client
+            .get_bucket_versioning()
+            .bucket(bucket_name)
+            .send()
+            .await
+        {
+            Ok(resp) => Ok(resp.status.is_some()),
+            Err(e) if is_unsupported_feature(e.code()) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub async fn list_multipart_uploads(
+        &self,
+        bucket_name: &str,
+    ) -> Result<Vec<PendingUpload>, Error> {
+        let mut uploads = Vec::new();
+        let mut key_marker = None;
+        let mut upload_id_marker = None;
+
+        loop {
+            let resp = self
+                .client
+                .list_multipart_uploads()
+                .bucket(bucket_name)
+                .set_key_marker(key_marker)
+                .set_upload_id_marker(upload_id_marker)
+                .send()
+                .await?;
+
+            for upload in resp.uploads() {
+                if let (Some(key), Some(upload_id)) = (upload.key(), upload.upload_id()) {
+                    uploads.push(PendingUpload {
+                        key: key.to_string(),
+                        upload_id: upload_id.to_string(),
+                    });
+                }
+            }
+
+            if !resp.is_truncated.unwrap_or(false) {
+                break;
+            }
+
+            key_marker = resp.next_key_marker;
+            upload_id_marker = resp.next_upload_id_marker;
+        }
+
+        Ok(uploads)
+    }
+
+    pub async fn abort_multipart_upload(
+        &self,
+        bucket_name: &str,
+        upload: &PendingUpload,
+    ) -> Result<(), Error> {
+        self.client
+            .abort_multipart_upload()
+            .bucket(bucket_name)
+            .key(&upload.key)
+            .upload_id(&upload.upload_id)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn list_objects(
         &self,
         bucket_name: &str,
         prefix: Option<&str>,
-        recursive: bool,
+        delimiter: Option<&str>,
         region: Option<String>,
     ) -> Result<Vec<ObjectInfo>, Error> {
         let mut all_objects = Vec::new();
-        let mut continuation_token: Option<String> = None;
+        let mut pager = self.pager(bucket_name, prefix, delimiter, region);
 
-        loop {
-            let mut request = self.client.list_objects_v2().bucket(bucket_name);
+        while let Some(objects) = pager.next_page().await? {
+            all_objects.extend(objects);
+        }
 
-            if let Some(prefix) = prefix {
-                request = request.prefix(prefix);
-            }
+        Ok(all_objects)
+    }
+
+    pub fn pager(
+        &self,
+        bucket_name: &str,
+        prefix: Option<&str>,
+        delimiter: Option<&str>,
+        region: Option<String>,
+    ) -> ObjectPager {
+        ObjectPager {
+            service: self.clone(),
+            bucket_name: bucket_name.to_string(),
+            prefix: prefix.map(|p| p.to_string()),
+            delimiter: delimiter.map(|d| d.to_string()),
+            folder_delimiter: delimiter.unwrap_or(DEFAULT_DELIMITER).to_string(),
+            region,
+            continuation_token: None,
+            done: false,
+        }
+    }
+
+    pub async fn list_objects_page(
+        &self,
+        bucket_name: &str,
+        prefix: Option<&str>,
+        delimiter: Option<&str>,
+        folder_delimiter: &str,
+        region: Option<String>,
+        continuation_token: Option<String>,
+    ) -> Result<ObjectPage, Error> {
+        let mut objects = Vec::new();
+        let mut request = self
+            .client
+            .list_objects_v2()
+            .bucket(bucket_name)
+            .encoding_type(EncodingType::Url)
+            .fetch_owner(true);
 
-            if !recursive {
-                request = request.delimiter("/");
+        // Other S3 compatible stores don't know about optional attributes
+        if self.provider == BucketProvider::S3 {
+            request = request.optional_object_attributes(OptionalObjectAttributes::RestoreStatus);
+        }
+
+        if let Some(prefix) = prefix {
+            request = request.prefix(prefix);
+        }
+
+        // Without a delimiter every key below the prefix is listed
+        if let Some(delimiter) = delimiter {
+            request = request.delimiter(delimiter);
+        }
+
+        if let Some(token) = continuation_token {
+            request = request.continuation_token(token);
+        }
+
+        let resp = request.send().await?;
+
+        // Keys may contain characters that XML can't carry. Stores that ignore
+        // the encoding type don't echo it back, their keys are used as is.
+        let decode = |key: &str| match resp.encoding_type() {
+            Some(EncodingType::Url) => url::decode_key(key),
+            _ => key.to_string(),
+        };
+
+        for prefix in resp.common_prefixes() {
+            if let Some(prefix_str) = prefix.prefix() {
+                let prefix_str = decode(prefix_str);
+                let url = self.get_object_url(bucket_name, &prefix_str, region.clone());
+                objects.push(ObjectInfo {
+                    key: prefix_str,
+                    size: None,
+                    last_modified: None,
+                    storage_class: None,
+                    is_folder: true,
+                    kind: ObjectKind::Prefix,
+                    url,
+                    etag: None,
+                    checksum_algorithms: Vec::new(),
+                    owner: None,
+                    restore_status: None,
+                });
             }
+        }
 
-            if let Some(token) = continuation_token {
-                request = request.continuation_token(token);
+        for object in resp.contents() {
+            if let Some(key) = object.key() {
+                objects.push(self.object_info(
+                    bucket_name,
+                    decode(key),
+                    object,
+                    folder_delimiter,
+                    region.clone(),
+                ));
             }
+        }
+
+        let next_continuation_token = if resp.is_truncated() == Some(true) {
+            resp.next_continuation_token().map(|s| s.to_string())
+        } else {
+            None
+        };
 
-            let resp = request.send().await?;
+        Ok(ObjectPage {
+            objects,
+            next_continuation_token,
+        })
+    }
 
-            for prefix in resp.common_prefixes() {
-                if let Some(prefix_str) = prefix.prefix() {
-                    let url = self.get_object_url(bucket_name, prefix_str, region.clone());
-                    all_objects.push(ObjectInfo {
-                        key: prefix_str.to_string(),
-                        size: None,
-                        last_modified: None,
-                        storage_class: None,
-                        is_folder: true,
-                        url,
-                    });
-                }
+    fn object_info(
+        &self,
+        bucket_name: &str,
+        key: String,
+        object: &Object,
+        folder_delimiter: &str,
+        region: Option<String>,
+    ) -> ObjectInfo {
+        let is_folder_marker = key.ends_with(folder_delimiter);
+        let url = self.get_object_url(bucket_name, &key, region);
+
+        ObjectInfo {
+            key,
+            size: object.size(),
+            last_modified: object.last_modified().map(|date| date.to_string()),
+            storage_class: object.storage_class().map(|sc| sc.as_str().to_string()),
+            is_folder: is_folder_marker,
+            kind: if is_folder_marker {
+                ObjectKind::FolderMarker
+            } else {
+                ObjectKind::File
+            },
+            url,
+            etag: object
+                .e_tag()
+                .map(|etag| etag.trim_matches('"').to_string()),
+            checksum_algorithms: object
+                .checksum_algorithm()
+                .iter()
+                .map(|algorithm| algorithm.as_str().to_string())
+                .collect(),
+            owner: object.owner().map(|owner| ObjectOwner {
+                id: owner.id().map(|id| id.to_string()),
+                display_name: owner.display_name().map(|name| name.to_string()),
+            }),
+            restore_status: object.restore_status().map(|status| RestoreStatus {
+                is_restore_in_progress: status.is_restore_in_progress().unwrap_or(false),
+                restore_expiry_date: status.restore_expiry_date().map(|date| date.to_string()),
+            }),
+        }
+    }
+
+    pub fn version_pager(&self, bucket_name: &str, prefix: Option<&str>) -> VersionPager {
+        VersionPager {
+            service: self.clone(),
+            bucket_name: bucket_name.to_string(),
+            prefix: prefix.map(|p| p.to_string()),
+            key_marker: None,
+            version_id_marker: None,
+            done: false,
+        }
+    }
+
+    pub async fn list_object_versions_page(
+        &self,
+        bucket_name: &str,
+        prefix: Option<&str>,
+        key_marker: Option<String>,
+        version_id_marker: Option<String>,
+    ) -> Result<VersionPage, Error> {
+        let resp = self
+            .client
+            .list_object_versions()
+            .bucket(bucket_name)
+            .encoding_type(EncodingType::Url)
+            .set_prefix(prefix.map(|p| p.to_string()))
+            .set_key_marker(key_marker)
+            .set_version_id_marker(version_id_marker)
+            .send()
+            .await?;
+
+        let decode = |key: &str| match resp.encoding_type() {
+            Some(EncodingType::Url) => url::decode_key(key),
+            _ => key.to_string(),
+        };
+
+        let mut entries = Vec::new();
+
+        for version in resp.versions() {
+            entries.push((
+                version.last_modified().cloned(),
+                ObjectVersion {
+                    key: decode(version.key().unwrap_or_default()),
+                    version_id: version.version_id().unwrap_or("null").to_string(),
+                    is_latest: version.is_latest().unwrap_or(false),
+                    is_delete_marker: false,
+                    size: version.size(),
+                    last_modified: version.last_modified().map(|date| date.to_string()),
+                    etag: version
+                        .e_tag()
+                        .map(|etag| etag.trim_matches('"').to_string()),
+                    storage_class: version.storage_class().map(|sc| sc.as_str().to_string()),
+                },
+            ));
+        }
+
+        for marker in resp.delete_markers() {
+            entries.push((
+                marker.last_modified().cloned(),
+                ObjectVersion {
+                    key: decode(marker.key().unwrap_or_default()),
+                    version_id: marker.version_id().unwrap_or("null").to_string(),
+                    is_latest: marker.is_latest().unwrap_or(false),
+                    is_delete_marker: true,
+                    size: None,
+                    last_modified: marker.last_modified().map(|date| date.to_string()),
+                    etag: None,
+                    storage_class: None,
+                },
+            ));
+        }
+
+        // Versions and delete markers come back as two lists, interleave them
+        // again so every key reads newest first
+        entries.sort_by(|(a_date, a), (b_date, b)| {
+            a.key
+                .cmp(&b.key)
+                .then_with(|| b.is_latest.cmp(&a.is_latest))
+                .then_with(|| b_date.cmp(a_date))
+        });
+
+        let (next_key_marker, next_version_id_marker) = if resp.is_truncated() == Some(true) {
+            (
+                resp.next_key_marker().map(decode),
+                resp.next_version_id_marker().map(|s| s.to_string()),
+            )
+        } else {
+            (None, None)
+        };
+
+        Ok(VersionPage {
+            versions: entries.into_iter().map(|(_, version)| version).collect(),
+            next_key_marker,
+            next_version_id_marker,
+        })
+    }
+
+    pub async fn list_object_versions(
+        &self,
+        bucket_name: &str,
+        prefix: Option<&str>,
+    ) -> Result<Vec<ObjectVersion>, Error> {
+        let mut pager = self.version_pager(bucket_name, prefix);
+        let mut versions = Vec::new();
+
+        while let Some(page) = pager.next_page().await? {
+            versions.extend(page);
+        }
+
+        Ok(versions)
+    }
+
+    // Copies an old version over the current one, which makes it the latest
+    // version again without touching the rest of the history.
+    pub async fn restore_object_version(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: &str,
+    ) -> Result<Option<String>, Error> {
+        let resp = self
+            .client
+            .copy_object()
+            .bucket(bucket_name)
+            .key(key)
+            .copy_source(url::copy_source(bucket_name, key, Some(version_id)))
+            .send()
+            .await?;
+
+        Ok(resp.version_id().map(|v| v.to_string()))
+    }
+
+    // The delete marker on top of the version stack, None when the object
+    // wasn't deleted
+    pub async fn latest_delete_marker(
+        &self,
+        bucket_name: &str,
+        key: &str,
+    ) -> Result<Option<ObjectVersion>, Error> {
+        let mut pager = self.version_pager(bucket_name, Some(key));
+
+        // Versions come by key and newest first, and `key` sorts before every
+        // longer key it's a prefix of. The first version listed decides, the
+        // rest of the subtree is never paged through.
+        while let Some(page) = pager.next_page().await? {
+            if let Some(version) = page.into_iter().next() {
+                let is_marker = version.key == key && version.is_latest && version.is_delete_marker;
+
+                return Ok(is_marker.then_some(version));
             }
+        }
 
-            for object in resp.contents() {
-                if let Some(key) = object.key() {
-                    if !key.ends_with('/') {
-                        let url = self.get_object_url(bucket_name, key, region.clone());
-                        all_objects.push(ObjectInfo {
-                            key: key.to_string(),
-                            size: object.size(),
-                            last_modified: object.last_modified().map(|date| date.to_string()),
-                            storage_class: object.storage_class().map(|sc| sc.as_str().to_string()),
-                            is_folder: false,
-                            url,
-                        });
-                    }
-                }
+        Ok(None)
+    }
+
+    // Permanently deletes specific versions in DeleteObjects batches. Returns
+    // the versions S3 refused to delete.
+    pub async fn delete_object_versions(
+        &self,
+        bucket_name: &str,
+        versions: &[ObjectVersion],
+    ) -> Result<Vec<FailedDelete>, Box<dyn std::error::Error + Send + Sync>> {
+        let targets = versions
+            .iter()
+            .map(|version| (version.key.as_str(), Some(version.version_id.as_str())))
+            .collect();
+
+        self.delete_batched(bucket_name, targets).await
+    }
+
+    // DeleteObjects in batches without version IDs, on a versioned bucket this
+    // adds delete markers. Returns the keys S3 refused to delete.
+    pub async fn delete_keys(
+        &self,
+        bucket_name: &str,
+        keys: &[String],
+    ) -> Result<Vec<FailedDelete>, Box<dyn std::error::Error + Send + Sync>> {
+        let targets = keys.iter().map(|key| (key.as_str(), None)).collect();
+
+        self.delete_batched(bucket_name, targets).await
+    }
+
+    // Keys XML can't carry are left out of the DeleteObjects batches and
+    // deleted one at a time, DeleteObject sends the key in the URL instead.
+    async fn delete_batched(
+        &self,
+        bucket_name: &str,
+        targets: Vec<(&str, Option<&str>)>,
+    ) -> Result<Vec<FailedDelete>, Box<dyn std::error::Error + Send + Sync>> {
+        const BATCH_SIZE: usize = 1000;
+        let mut errors = Vec::new();
+
+        let (batched, single): (Vec<_>, Vec<_>) =
+            targets.into_iter().partition(|(key, _)| is_xml_safe(key));
+
+        for chunk in batched.chunks(BATCH_SIZE) {
+            let identifiers = chunk
+                .iter()
+                .map(|(key, version_id)| {
+                    ObjectIdentifier::builder()
+                        .key(*key)
+                        .set_version_id(version_id.map(|v| v.to_string()))
+                        .build()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let delete = Delete::builder()
+                .set_objects(Some(identifiers))
+                .quiet(true)
+                .build()?;
+
+            let resp = self
+                .client
+                .delete_objects()
+                .bucket(bucket_name)
+                .delete(delete)
+                .send()
+                .await?;
+
+            for error in resp.errors() {
+                errors.push(FailedDelete {
+                    key: error.key().unwrap_or_default().to_string(),
+                    version_id: error.version_id().map(|v| v.to_string()),
+                    message: error.message().unwrap_or("Unknown error").to_string(),
+                });
             }
+        }
 
-            if resp.is_truncated() == Some(true) {
-                continuation_token = resp.next_continuation_token().map(|s| s.to_string());
-            } else {
-                break;
+        for (key, version_id) in single {
+            let result = self
+                .client
+                .delete_object()
+                .bucket(bucket_name)
+                .key(key)
+                .set_version_id(version_id.map(|v| v.to_string()))
+                .send()
+                .await;
+
+            if let Err(e) = result {
+                errors.push(FailedDelete {
+                    key: key.to_string(),
+                    version_id: version_id.map(|v| v.to_string()),
+                    message: Error::from(e).to_string(),
+                });
             }
         }
 
-        Ok(all_objects)
+        Ok(errors)
     }
 
     pub async fn upload_objects(
@@ -240,22 +1008,9 @@ impl S3Service {
             let client_clone = self.client.clone();
 
             let handle = tokio::spawn(async move {
-                let file_name = path
-                    .file_name()
-                    .and_then(|name| name.to_str())
+                let key = upload_key(prefix_clone.as_deref(), &path)
                     .ok_or("Failed to convert path to string")?;
 
-                let key = match prefix_clone {
-                    Some(p) if !p.is_empty() => {
-                        if p.ends_with('/') {
-                            format!("{}{}", p, file_name)
-                        } else {
-                            format!("{}/{}", p, file_name)
-                        }
-                    }
-                    _ => file_name.to_string(),
-                };
-
                 let mut file = File::open(&path).await?;
                 let mut contents = Vec::new();
                 file.read_to_end(&mut contents).await?;
@@ -334,12 +1089,14 @@ impl S3Service {
         &self,
         bucket_name: &str,
         key: &str,
+        version_id: Option<&str>,
     ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         let resp = self
             .client
             .get_object()
             .bucket(bucket_name)
             .key(key)
+            .set_version_id(version_id.map(|v| v.to_string()))
             .send()
             .await?;
 
@@ -347,6 +1104,529 @@ impl S3Service {
         Ok(data)
     }
 
+    // Streams the body to a local file instead of collecting it in memory
+    pub async fn download_object_to_file(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        path: &Path,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut resp = self
+            .client
+            .get_object()
+            .bucket(bucket_name)
+            .key(key)
+            .send()
+            .await?;
+
+        let mut file = File::create(path).await?;
+
+        while let Some(chunk) = resp.body.try_next().await? {
+            file.write_all(&chunk).await?;
+        }
+
+        file.flush().await?;
+
+        Ok(())
+    }
+
+    // Streams the body through the hasher so large objects are never held in
+    // memory as a whole.
+    pub async fn sha256_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let mut resp = self
+            .client
+            .get_object()
+            .bucket(bucket_name)
+            .key(key)
+            .send()
+            .await?;
+
+        let mut hasher = Sha256::new();
+
+        while let Some(chunk) = resp.body.try_next().await? {
+            hasher.update(&chunk);
+        }
+
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    // Copies an object into a bucket of `destination`, which may belong to a
+    // different connection. Within one connection the copy happens server side,
+    // otherwise the body is streamed from here to the destination together
+    // with its content headers and user metadata.
+    pub async fn copy_object_to(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        destination: &S3Service,
+        destination_bucket: &str,
+        destination_key: &str,
+        same_connection: bool,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if same_connection {
+            let head = self
+                .client
+                .head_object()
+                .bucket(bucket_name)
+                .key(key)
+                .send()
+                .await?;
+
+            if head.content_length().unwrap_or(0) <= MAX_COPY_OBJECT_SIZE {
+                destination
+                    .client
+                    .copy_object()
+                    .bucket(destination_bucket)
+                    .key(destination_key)
+                    .copy_source(url::copy_source(bucket_name, key, None))
+                    .send()
+                    .await?;
+
+                return Ok(());
+            }
+        }
+
+        let mut resp = self
+            .client
+            .get_object()
+            .bucket(bucket_name)
+            .key(key)
+            .send()
+            .await?;
+        let size = resp.content_length().unwrap_or(0);
+
+        if size <= MAX_PUT_OBJECT_SIZE {
+            destination
+                .client
+                .put_object()
+                .bucket(destination_bucket)
+                .key(destination_key)
+                .set_content_type(resp.content_type().map(|v| v.to_string()))
+                .set_cache_control(resp.cache_control().map(|v| v.to_string()))
+                .set_content_disposition(resp.content_disposition().map(|v| v.to_string()))
+                .set_content_encoding(resp.content_encoding().map(|v| v.to_string()))
+                .set_content_language(resp.content_language().map(|v| v.to_string()))
+                .set_metadata(resp.metadata().cloned())
+                .content_length(size)
+                .body(resp.body)
+                .send()
+                .await?;
+
+            return Ok(());
+        }
+
+        let upload = destination
+            .client
+            .create_multipart_upload()
+            .bucket(destination_bucket)
+            .key(destination_key)
+            .set_content_type(resp.content_type().map(|v| v.to_string()))
+            .set_cache_control(resp.cache_control().map(|v| v.to_string()))
+            .set_content_disposition(resp.content_disposition().map(|v| v.to_string()))
+            .set_content_encoding(resp.content_encoding().map(|v| v.to_string()))
+            .set_content_language(resp.content_language().map(|v| v.to_string()))
+            .set_metadata(resp.metadata().cloned())
+            .send()
+            .await?;
+        let upload_id = upload
+            .upload_id()
+            .ok_or("Multipart upload has no upload id")?
+            .to_string();
+
+        // Parts are buffered one at a time, sized so the part count stays
+        // within the 10,000 part limit
+        let part_size = (size as u64).div_ceil(MAX_PART_COUNT).max(MIN_PART_SIZE) as usize;
+        let mut parts = Vec::new();
+        let mut buffer = Vec::with_capacity(part_size);
+
+        let result: Result<(), Box<dyn std::error::Error + Send + Sync>> = async {
+            loop {
+                let chunk = resp.body.try_next().await?;
+
+                if let Some(chunk) = &chunk {
+                    buffer.extend_from_slice(chunk);
+                }
+
+                if buffer.len() >= part_size || (chunk.is_none() && !buffer.is_empty()) {
+                    let part_number = parts.len() as i32 + 1;
+                    let part = destination
+                        .client
+                        .upload_part()
+                        .bucket(destination_bucket)
+                        .key(destination_key)
+                        .upload_id(&upload_id)
+                        .part_number(part_number)
+                        .body(ByteStream::from(std::mem::take(&mut buffer)))
+                        .send()
+                        .await?;
+
+                    parts.push(
+                        CompletedPart::builder()
+                            .part_number(part_number)
+                            .set_e_tag(part.e_tag().map(|etag| etag.to_string()))
+                            .build(),
+                    );
+                }
+
+                if chunk.is_none() {
+                    break;
+                }
+            }
+
+            destination
+                .client
+                .complete_multipart_upload()
+                .bucket(destination_bucket)
+                .key(destination_key)
+                .upload_id(&upload_id)
+                .multipart_upload(
+                    CompletedMultipartUpload::builder()
+                        .set_parts(Some(parts))
+                        .build(),
+                )
+                .send()
+                .await?;
+
+            Ok(())
+        }
+        .await;
+
+        if result.is_err() {
+            // Leftover parts are billed until the upload is aborted
+            let _ = destination
+                .client
+                .abort_multipart_upload()
+                .bucket(destination_bucket)
+                .key(destination_key)
+                .upload_id(&upload_id)
+                .send()
+                .await;
+        }
+
+        result
+    }
+
+    pub async fn get_object_details(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<ObjectDetails, Error> {
+        let mut head_req = self
+            .client
+            .head_object()
+            .bucket(bucket_name)
+            .key(key)
+            .checksum_mode(ChecksumMode::Enabled);
+        let mut tagging_req = self
+            .client
+            .get_object_tagging()
+            .bucket(bucket_name)
+            .key(key);
+
+        if let Some(version_id) = version_id {
+            head_req = head_req.version_id(version_id);
+            tagging_req = tagging_req.version_id(version_id);
+        }
+
+        let (head_resp, tagging_resp) = tokio::join!(head_req.send(), tagging_req.send());
+        let head = head_resp?;
+
+        let tags = match tagging_resp {
+            Ok(resp) => Some(
+                resp.tag_set()
+                    .iter()
+                    .map(|tag| ObjectTag {
+                        key: tag.key().to_string(),
+                        value: tag.value().to_string(),
+                    })
+                    .collect(),
+            ),
+            // Reading tags needs its own permission, the details are still
+            // useful without them
+            Err(e) if is_unsupported_feature(e.code()) || e.code() == Some("AccessDenied") => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        let checksums = [
+            ("CRC32", head.checksum_crc32()),
+            ("CRC32C", head.checksum_crc32_c()),
+            ("SHA1", head.checksum_sha1()),
+            ("SHA256", head.checksum_sha256()),
+        ]
+        .into_iter()
+        .filter_map(|(algorithm, value)| {
+            value.map(|value| ObjectChecksum {
+                algorithm: algorithm.to_string(),
+                value: value.to_string(),
+            })
+        })
+        .collect();
+
+        Ok(ObjectDetails {
+            key: key.to_string(),
+            version_id: head.version_id().map(|v| v.to_string()),
+            content_type: head.content_type().map(|v| v.to_string()),
+            content_length: head.content_length(),
+            etag: head.e_tag().map(|etag| etag.trim_matches('"').to_string()),
+            last_modified: head.last_modified().map(|date| date.to_string()),
+            storage_class: head.storage_class().map(|sc| sc.as_str().to_string()),
+            cache_control: head.cache_control().map(|v| v.to_string()),
+            content_disposition: head.content_disposition().map(|v| v.to_string()),
+            content_encoding: head.content_encoding().map(|v| v.to_string()),
+            content_language: head.content_language().map(|v| v.to_string()),
+            expires: head.expires_string().map(|v| v.to_string()),
+            website_redirect_location: head.website_redirect_location().map(|v| v.to_string()),
+            metadata: head.metadata().cloned().unwrap_or_default(),
+            server_side_encryption: head
+                .server_side_encryption()
+                .map(|sse| sse.as_str().to_string()),
+            sse_kms_key_id: head.ssekms_key_id().map(|v| v.to_string()),
+            sse_customer_algorithm: head.sse_customer_algorithm().map(|v| v.to_string()),
+            bucket_key_enabled: head.bucket_key_enabled(),
+            object_lock_mode: head
+                .object_lock_mode()
+                .map(|mode| mode.as_str().to_string()),
+            object_lock_retain_until_date: head
+                .object_lock_retain_until_date()
+                .map(|date| date.to_string()),
+            object_lock_legal_hold_status: head
+                .object_lock_legal_hold_status()
+                .map(|status| status.as_str().to_string()),
+            replication_status: head
+                .replication_status()
+                .map(|status| status.as_str().to_string()),
+            restore_status: head.restore().map(parse_restore_header),
+            checksums,
+            parts_count: head.parts_count(),
+            tags,
+        })
+    }
+
+    pub async fn presign_get_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        expires_in: Duration,
+        overrides: &ResponseOverrides,
+    ) -> Result<PresignedUrl, Box<dyn std::error::Error + Send + Sync>> {
+        let start_time = SystemTime::now();
+        let presigning_config = PresigningConfig::builder()
+            .start_time(start_time)
+            .expires_in(expires_in)
+            .build()?;
+
+        let presigned = self
+            .client
+            .get_object()
+            .bucket(bucket_name)
+            .key(key)
+            .set_response_content_disposition(overrides.content_disposition.clone())
+            .set_response_content_type(overrides.content_type.clone())
+            .set_response_cache_control(overrides.cache_control.clone())
+            .set_response_content_language(overrides.content_language.clone())
+            .presigned(presigning_config)
+            .await?;
+
+        Ok(PresignedUrl {
+            key: key.to_string(),
+            url: presigned.uri().to_string(),
+            expires_at: DateTime::from(start_time + expires_in).to_string(),
+        })
+    }
+
+    pub async fn presign_put_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        expires_in: Duration,
+        content_type: Option<String>,
+    ) -> Result<PresignedUpload, Box<dyn std::error::Error + Send + Sync>> {
+        let start_time = SystemTime::now();
+        let presigning_config = PresigningConfig::builder()
+            .start_time(start_time)
+            .expires_in(expires_in)
+            .build()?;
+
+        // A content type becomes a signed header the uploader has to send as is
+        let presigned = self
+            .client
+            .put_object()
+            .bucket(bucket_name)
+            .key(key)
+            .set_content_type(content_type.clone())
+            .presigned(presigning_config)
+            .await?;
+
+        let url = presigned.uri().to_string();
+
+        Ok(PresignedUpload {
+            key: key.to_string(),
+            curl_command: put_curl_command(&url, content_type.as_deref()),
+            url,
+            content_type,
+            expires_at: DateTime::from(start_time + expires_in).to_string(),
+        })
+    }
+
+    pub fn presign_post_policy(
+        &self,
+        policy: &PostPolicy,
+        expires_in: Duration,
+    ) -> Result<PresignedPost, String> {
+        if self.provider == BucketProvider::R2 {
+            return Err("R2 doesn't support POST uploads, use a presigned PUT URL".to_string());
+        }
+
+        let bucket_url = url::bucket_url(
+            &self.provider,
+            &self.endpoint_url,
+            policy.bucket_name,
+            Some(&self.region),
+        );
+
+        let credentials = SigningCredentials {
+            access_key_id: self.credentials.access_key_id(),
+            secret_access_key: self.credentials.secret_access_key(),
+            session_token: self.credentials.session_token(),
+            region: &self.region,
+        };
+
+        sign_post_policy(
+            policy,
+            &bucket_url,
+            &credentials,
+            SystemTime::now(),
+            expires_in,
+        )
+    }
+
+    // Rewrites the headers of an object by copying it onto itself with
+    // `MetadataDirective=REPLACE`. Everything not covered by `changes` is
+    // carried over from the current object.
+    async fn object_acl_grants(
+        &self,
+        bucket_name: &str,
+        key: &str,
+    ) -> Result<Option<metadata::AclGrants>, Box<dyn std::error::Error + Send + Sync>> {
+        let acl = match self
+            .client
+            .get_object_acl()
+            .bucket(bucket_name)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(acl) => acl,
+            // Providers without ACLs have nothing to lose
+            Err(e) if is_unsupported_feature(e.code()) => return Ok(None),
+            Err(e) => {
+                return Err(format!(
+                    "Failed to read the object ACL to keep it: {}",
+                    Error::from(e)
+                )
+                .into())
+            }
+        };
+
+        let owner_id = acl.owner().and_then(|owner| owner.id());
+
+        Ok(metadata::acl_grants(owner_id, acl.grants()))
+    }
+
+    pub async fn update_object_metadata(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        changes: &MetadataChanges,
+        dry_run: bool,
+    ) -> Result<MetadataUpdate, Box<dyn std::error::Error + Send + Sync>> {
+        let head = self
+            .client
+            .head_object()
+            .bucket(bucket_name)
+            .key(key)
+            .checksum_mode(ChecksumMode::Enabled)
+            .send()
+            .await?;
+
+        let before = ObjectHeaders::from_head(&head);
+        let after = before.apply(changes);
+
+        if !dry_run && after != before {
+            if head.content_length().unwrap_or(0) > MAX_COPY_OBJECT_SIZE {
+                return Err("Object is larger than 5 GiB and can't be updated in place".into());
+            }
+
+            if head.sse_customer_algorithm().is_some() {
+                return Err(
+                    "Objects encrypted with a customer provided key can't be updated".into(),
+                );
+            }
+
+            let expires = after
+                .expires
+                .as_deref()
+                .map(|expires| DateTime::from_str(expires, DateTimeFormat::HttpDate))
+                .transpose()
+                .map_err(|e| format!("Invalid Expires header: {}", e))?;
+
+            let mut req = self
+                .client
+                .copy_object()
+                .bucket(bucket_name)
+                .key(key)
+                .copy_source(url::copy_source(bucket_name, key, None))
+                .metadata_directive(MetadataDirective::Replace)
+                .set_metadata(Some(after.metadata.clone()))
+                .set_content_type(after.content_type.clone())
+                .set_cache_control(after.cache_control.clone())
+                .set_content_disposition(after.content_disposition.clone())
+                .set_content_encoding(after.content_encoding.clone())
+                .set_content_language(after.content_language.clone())
+                .set_expires(expires)
+                .set_website_redirect_location(
+                    head.website_redirect_location().map(|v| v.to_string()),
+                )
+                .set_storage_class(head.storage_class().cloned())
+                .set_checksum_algorithm(metadata::checksum_algorithm(&head));
+
+            // A copy only keeps the ACL of its source when it's passed along,
+            // otherwise a public object would turn private
+            if let Some(grants) = self.object_acl_grants(bucket_name, key).await? {
+                req = req
+                    .set_grant_full_control(grants.full_control)
+                    .set_grant_read(grants.read)
+                    .set_grant_read_acp(grants.read_acp)
+                    .set_grant_write_acp(grants.write_acp);
+            }
+
+            // A copy is re-encrypted with the bucket default unless told otherwise
+            if let Some(sse) = head.server_side_encryption() {
+                req = req.server_side_encryption(sse.clone());
+
+                if *sse != ServerSideEncryption::Aes256 {
+                    req = req
+                        .set_ssekms_key_id(head.ssekms_key_id().map(|v| v.to_string()))
+                        .set_bucket_key_enabled(head.bucket_key_enabled());
+                }
+            }
+
+            req.send().await?;
+        }
+
+        Ok(MetadataUpdate {
+            key: key.to_string(),
+            before: Some(before),
+            after: Some(after),
+            error: None,
+        })
+    }
+
     pub async fn download_objects(
         &self,
         bucket_name: &str,
@@ -360,7 +1640,7 @@ impl S3Service {
 
             let handle = tokio::spawn(async move {
                 let data = s3_service_clone
-                    .download_object(&bucket_name_clone, &key)
+                    .download_object(&bucket_name_clone, &key, None)
                     .await?;
                 Ok((key, data))
             });
@@ -393,7 +1673,7 @@ impl S3Service {
         region: Option<String>,
     ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         let objects = self
-            .list_objects(bucket_name, Some(prefix), true, region)
+            .list_objects(bucket_name, Some(prefix), None, region)
             .await?;
 
         let file_keys: Vec<String> = objects
@@ -481,7 +1761,12 @@ impl S3Service {
             }
 
             let objects = self
-                .list_objects(bucket_name, Some(&current_prefix), false, region.clone())
+                .list_objects(
+                    bucket_name,
+                    Some(&current_prefix),
+                    Some(DEFAULT_DELIMITER),
+                    region.clone(),
+                )
                 .await?;
 
             for obj in objects {
@@ -502,28 +1787,12 @@ impl S3Service {
             all_keys.push(folder_prefix_with_slash);
         }
 
-        const BATCH_SIZE: usize = 1000;
-
-        for chunk in all_keys.chunks(BATCH_SIZE) {
-            let keys_to_delete: Vec<ObjectIdentifier> = chunk
-                .iter()
-                .map(|key| ObjectIdentifier::builder().key(key).build().unwrap())
-                .collect();
-
-            let delete = Delete::builder()
-                .set_objects(Some(keys_to_delete))
-                .quiet(true)
-                .build()?;
+        let failed = self.delete_keys(bucket_name, &all_keys).await?;
 
-            self.client
-                .delete_objects()
-                .bucket(bucket_name)
-                .delete(delete)
-                .send()
-                .await?;
+        match failed.first() {
+            Some(failure) => Err(format!("Failed to delete {}", failure).into()),
+            None => Ok(()),
         }
-
-        Ok(())
     }
 
     pub async fn move_objects(
@@ -541,20 +1810,12 @@ impl S3Service {
             let destination_prefix_clone = destination_prefix.to_string();
 
             let handle = tokio::spawn(async move {
-                let filename = key.rsplit('/').next().unwrap_or(&key);
-
-                let destination_key = if destination_prefix_clone.is_empty() {
-                    filename.to_string()
-                } else if destination_prefix_clone.ends_with('/') {
-                    format!("{}{}", destination_prefix_clone, filename)
-                } else {
-                    format!("{}/{}", destination_prefix_clone, filename)
-                };
+                let destination_key = move_destination_key(&key, &destination_prefix_clone);
 
                 client_clone
                     .copy_object()
                     .bucket(&bucket_name_clone)
-                    .copy_source(format!("{}/{}", bucket_name_clone, key))
+                    .copy_source(url::copy_source(&bucket_name_clone, &key, None))
                     .key(&destination_key)
                     .send()
                     .await?;
@@ -585,3 +1846,58 @@ impl S3Service {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_feature_codes() {
+        assert!(is_unsupported_feature(Some("NotImplemented")));
+        assert!(is_unsupported_feature(Some("MethodNotAllowed")));
+        assert!(!is_unsupported_feature(Some("AccessDenied")));
+        assert!(!is_unsupported_feature(None));
+    }
+
+    #[test]
+    fn restore_header_is_parsed() {
+        let status = parse_restore_header(
+            "ongoing-request=\"false\", expiry-date=\"Fri, 21 Dec 2012 00:00:00 GMT\"",
+        );
+
+        assert!(!status.is_restore_in_progress);
+        assert_eq!(
+            status.restore_expiry_date.as_deref(),
+            Some("Fri, 21 Dec 2012 00:00:00 GMT")
+        );
+        assert!(parse_restore_header("ongoing-request=\"true\"").is_restore_in_progress);
+    }
+
+    #[test]
+    fn failed_delete_names_the_version() {
+        let failed = FailedDelete {
+            key: "a.txt".to_string(),
+            version_id: Some("v1".to_string()),
+            message: "AccessDenied".to_string(),
+        };
+
+        assert_eq!(failed.to_string(), "a.txt (v1): AccessDenied");
+        assert_eq!(
+            FailedDelete {
+                version_id: None,
+                ..failed
+            }
+            .to_string(),
+            "a.txt: AccessDenied"
+        );
+    }
+
+    #[test]
+    fn xml_safe_keys_exclude_most_control_characters() {
+        assert!(is_xml_safe("photos/2024/café 😀.png"));
+        assert!(is_xml_safe("tab\tnewline\ncarriage\r"));
+        assert!(!is_xml_safe("bell\u{7}"));
+        assert!(!is_xml_safe("nul\u{0}"));
+        assert!(!is_xml_safe("\u{FFFE}"));
+    }
+}
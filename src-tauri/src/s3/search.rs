@@ -0,0 +1,296 @@
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::{ObjectInfo, ObjectKind};
+
+pub const DEFAULT_MAX_SCANNED: u64 = 1_000_000;
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub enum KeyPattern {
+    Substring(String),
+    Glob(String),
+    Regex(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct SearchFilters {
+    pub pattern: Option<KeyPattern>,
+    pub case_sensitive: bool,
+    pub min_size: Option<i64>,
+    pub max_size: Option<i64>,
+    pub modified_after: Option<String>,
+    pub modified_before: Option<String>,
+    pub storage_classes: Vec<String>,
+    pub extensions: Vec<String>,
+}
+
+pub struct SearchMatcher {
+    prefix: String,
+    key_regex: Option<Regex>,
+    // Globs without a slash are matched against the file name only, so `*.png`
+    // finds images at any depth.
+    match_file_name: bool,
+    min_size: Option<i64>,
+    max_size: Option<i64>,
+    modified_after: Option<DateTime>,
+    modified_before: Option<DateTime>,
+    storage_classes: Vec<String>,
+    extensions: Vec<String>,
+}
+
+impl SearchMatcher {
+    pub fn new(prefix: Option<&str>, filters: &SearchFilters) -> Result<Self, String> {
+        let (pattern, match_file_name) = match &filters.pattern {
+            Some(KeyPattern::Substring(s)) => (Some(regex::escape(s)), false),
+            Some(KeyPattern::Glob(g)) => (Some(glob_to_regex(g)), !g.contains('/')),
+            Some(KeyPattern::Regex(r)) => (Some(r.clone()), false),
+            None => (None, false),
+        };
+
+        let key_regex = pattern
+            .map(|p| {
+                RegexBuilder::new(&p)
+                    .case_insensitive(!filters.case_sensitive)
+                    .build()
+                    .map_err(|e| format!("Invalid search pattern: {}", e))
+            })
+            .transpose()?;
+
+        Ok(SearchMatcher {
+            prefix: prefix.unwrap_or_default().to_string(),
+            key_regex,
+            match_file_name,
+            min_size: filters.min_size,
+            max_size: filters.max_size,
+            modified_after: parse_date(filters.modified_after.as_deref())?,
+            modified_before: parse_date(filters.modified_before.as_deref())?,
+            storage_classes: filters
+                .storage_classes
+                .iter()
+                .map(|sc| sc.to_uppercase())
+                .collect(),
+            extensions: filters
+                .extensions
+                .iter()
+                .map(|ext| ext.trim_start_matches('.').to_lowercase())
+                .collect(),
+        })
+    }
+
+    pub fn matches(&self, object: &ObjectInfo) -> bool {
+        if object.kind == ObjectKind::Prefix {
+            return false;
+        }
+
+        let relative_key = object.key.strip_prefix(&self.prefix).unwrap_or(&object.key);
+        let file_name = relative_key.rsplit('/').next().unwrap_or(relative_key);
+
+        if let Some(key_regex) = &self.key_regex {
+            let subject = if self.match_file_name {
+                file_name
+            } else {
+                relative_key
+            };
+
+            if !key_regex.is_match(subject) {
+                return false;
+            }
+        }
+
+        let size = object.size.unwrap_or(0);
+
+        if self.min_size.is_some_and(|min| size < min)
+            || self.max_size.is_some_and(|max| size > max)
+        {
+            return false;
+        }
+
+        if self.modified_after.is_some() || self.modified_before.is_some() {
+            let Some(last_modified) = parse_date(object.last_modified.as_deref()).ok().flatten()
+            else {
+                return false;
+            };
+
+            if self
+                .modified_after
+                .is_some_and(|after| last_modified < after)
+                || self
+                    .modified_before
+                    .is_some_and(|before| last_modified > before)
+            {
+                return false;
+            }
+        }
+
+        if !self.storage_classes.is_empty() {
+            // Providers omit the storage class for objects in the default tier
+            let storage_class = object.storage_class.as_deref().unwrap_or("STANDARD");
+
+            if !self.storage_classes.iter().any(|sc| sc == storage_class) {
+                return false;
+            }
+        }
+
+        if !self.extensions.is_empty() {
+            let Some((_, extension)) = file_name.rsplit_once('.') else {
+                return false;
+            };
+
+            if !self.extensions.contains(&extension.to_lowercase()) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn parse_date(date: Option<&str>) -> Result<Option<DateTime>, String> {
+    date.map(|d| {
+        DateTime::from_str(d, DateTimeFormat::DateTime)
+            .map_err(|e| format!("Invalid date '{}': {}", d, e))
+    })
+    .transpose()
+}
+
+// `*` and `?` stay within one path segment, `**` crosses slashes.
+fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                pattern.push_str(".*");
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    pattern.push('$');
+    pattern
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::test_support::object;
+
+    fn filters(pattern: Option<KeyPattern>) -> SearchFilters {
+        SearchFilters {
+            pattern,
+            case_sensitive: false,
+            min_size: None,
+            max_size: None,
+            modified_after: None,
+            modified_before: None,
+            storage_classes: Vec::new(),
+            extensions: Vec::new(),
+        }
+    }
+
+    fn glob_matches(glob: &str, subject: &str) -> bool {
+        Regex::new(&glob_to_regex(glob)).unwrap().is_match(subject)
+    }
+
+    #[test]
+    fn glob_wildcards_stay_within_a_segment() {
+        assert!(glob_matches("*.png", "cat.png"));
+        assert!(!glob_matches("*.png", "images/cat.png"));
+        assert!(glob_matches("images/?at.png", "images/cat.png"));
+        assert!(!glob_matches("images/?.png", "images//.png"));
+        assert!(glob_matches("images/**.png", "images/2024/03/cat.png"));
+    }
+
+    #[test]
+    fn glob_escapes_regex_characters() {
+        assert!(glob_matches("report (1).csv", "report (1).csv"));
+        assert!(!glob_matches("a.csv", "abcsv"));
+        assert!(glob_matches("[draft]+*", "[draft]+notes"));
+    }
+
+    #[test]
+    fn glob_without_slash_matches_the_file_name_at_any_depth() {
+        let matcher =
+            SearchMatcher::new(None, &filters(Some(KeyPattern::Glob("*.PNG".to_string()))))
+                .unwrap();
+
+        assert!(matcher.matches(&object("cat.png", 1)));
+        assert!(matcher.matches(&object("images/2024/cat.png", 1)));
+        assert!(!matcher.matches(&object("images/cat.png.bak", 1)));
+    }
+
+    #[test]
+    fn glob_with_slash_matches_the_key_below_the_prefix() {
+        let matcher = SearchMatcher::new(
+            Some("photos/"),
+            &filters(Some(KeyPattern::Glob("2024/*.jpg".to_string()))),
+        )
+        .unwrap();
+
+        assert!(matcher.matches(&object("photos/2024/beach.jpg", 1)));
+        assert!(!matcher.matches(&object("photos/2024/03/beach.jpg", 1)));
+    }
+
+    #[test]
+    fn substring_is_matched_literally() {
+        let mut filters = filters(Some(KeyPattern::Substring("a.b".to_string())));
+        filters.case_sensitive = true;
+        let matcher = SearchMatcher::new(None, &filters).unwrap();
+
+        assert!(matcher.matches(&object("x/a.b/y", 1)));
+        assert!(!matcher.matches(&object("x/axb/y", 1)));
+        assert!(!matcher.matches(&object("x/A.B/y", 1)));
+    }
+
+    #[test]
+    fn invalid_regex_is_rejected() {
+        let result = SearchMatcher::new(None, &filters(Some(KeyPattern::Regex("(".to_string()))));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn prefixes_never_match() {
+        let matcher = SearchMatcher::new(None, &filters(None)).unwrap();
+        let mut prefix = object("images/", 0);
+        prefix.kind = ObjectKind::Prefix;
+
+        assert!(!matcher.matches(&prefix));
+    }
+
+    #[test]
+    fn size_date_storage_class_and_extension_filters() {
+        let mut filters = filters(None);
+        filters.min_size = Some(10);
+        filters.max_size = Some(100);
+        filters.modified_after = Some("2024-01-01T00:00:00Z".to_string());
+        filters.modified_before = Some("2024-12-31T00:00:00Z".to_string());
+        filters.storage_classes = vec!["standard".to_string()];
+        filters.extensions = vec![".JPG".to_string()];
+        let matcher = SearchMatcher::new(None, &filters).unwrap();
+
+        assert!(matcher.matches(&object("a.jpg", 50)));
+        assert!(!matcher.matches(&object("a.jpg", 5)));
+        assert!(!matcher.matches(&object("a.jpg", 500)));
+        assert!(!matcher.matches(&object("a.png", 50)));
+        assert!(!matcher.matches(&object("jpg", 50)));
+
+        let mut old = object("a.jpg", 50);
+        old.last_modified = Some("2023-06-01T00:00:00Z".to_string());
+        assert!(!matcher.matches(&old));
+
+        let mut undated = object("a.jpg", 50);
+        undated.last_modified = None;
+        assert!(!matcher.matches(&undated));
+
+        let mut glacier = object("a.jpg", 50);
+        glacier.storage_class = Some("GLACIER".to_string());
+        assert!(!matcher.matches(&glacier));
+    }
+}
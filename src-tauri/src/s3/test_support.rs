@@ -0,0 +1,45 @@
+use super::versions::ObjectVersion;
+use super::{ObjectInfo, ObjectKind};
+
+// A plain file as listings return it, tests override the fields they care about
+pub fn object(key: &str, size: i64) -> ObjectInfo {
+    ObjectInfo {
+        key: key.to_string(),
+        size: Some(size),
+        last_modified: Some("2024-03-01T12:00:00Z".to_string()),
+        storage_class: None,
+        is_folder: false,
+        kind: ObjectKind::File,
+        url: String::new(),
+        etag: None,
+        checksum_algorithms: Vec::new(),
+        owner: None,
+        restore_status: None,
+    }
+}
+
+pub fn version(key: &str, version_id: &str, last_modified: &str, is_latest: bool) -> ObjectVersion {
+    ObjectVersion {
+        key: key.to_string(),
+        version_id: version_id.to_string(),
+        is_latest,
+        is_delete_marker: false,
+        size: Some(10),
+        last_modified: Some(last_modified.to_string()),
+        etag: None,
+        storage_class: None,
+    }
+}
+
+pub fn delete_marker(
+    key: &str,
+    version_id: &str,
+    last_modified: &str,
+    is_latest: bool,
+) -> ObjectVersion {
+    ObjectVersion {
+        is_delete_marker: true,
+        size: None,
+        ..version(key, version_id, last_modified, is_latest)
+    }
+}
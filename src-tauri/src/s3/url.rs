@@ -0,0 +1,180 @@
+use std::fmt::Write;
+
+use super::BucketProvider;
+
+// Percent-encodes a key byte by byte over its UTF-8 representation. Only the
+// RFC 3986 unreserved characters and `/` are left as is, so `café.png` becomes
+// `caf%C3%A9.png` and folder separators survive.
+pub fn encode_key(key: &str) -> String {
+    let mut encoded = String::with_capacity(key.len());
+
+    for byte in key.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => {
+                let _ = write!(encoded, "%{:02X}", byte);
+            }
+        }
+    }
+
+    encoded
+}
+
+// Virtual-hosted addressing puts the bucket into the host name, which only
+// works for names that are valid DNS labels. Dots are excluded as well since
+// they break the `*.s3.amazonaws.com` TLS certificate.
+fn supports_virtual_hosted_style(bucket_name: &str) -> bool {
+    (3..=63).contains(&bucket_name.len())
+        && bucket_name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        && !bucket_name.starts_with('-')
+        && !bucket_name.ends_with('-')
+}
+
+pub fn bucket_url(
+    provider: &BucketProvider,
+    endpoint_url: &str,
+    bucket_name: &str,
+    region: Option<&str>,
+) -> String {
+    match provider {
+        BucketProvider::S3 => {
+            let region = region.unwrap_or("us-east-1");
+
+            if supports_virtual_hosted_style(bucket_name) {
+                format!("https://{}.s3.{}.amazonaws.com", bucket_name, region)
+            } else {
+                format!("https://s3.{}.amazonaws.com/{}", region, bucket_name)
+            }
+        }
+        // R2 and most self-hosted stores default to path-style addressing
+        _ => format!("{}/{}", endpoint_url.trim_end_matches('/'), bucket_name),
+    }
+}
+
+// A public base URL (a CDN or an R2 custom domain) points at the bucket root,
+// so the key is appended to it directly.
+pub fn object_url(bucket_url: &str, public_base_url: Option<&str>, key: &str) -> String {
+    let base = public_base_url
+        .filter(|base| !base.is_empty())
+        .unwrap_or(bucket_url);
+
+    format!("{}/{}", base.trim_end_matches('/'), encode_key(key))
+}
+
+// The CopySource header has to be URL encoded like a path
+pub fn copy_source(bucket_name: &str, key: &str, version_id: Option<&str>) -> String {
+    let source = format!("{}/{}", bucket_name, encode_key(key));
+
+    match version_id {
+        Some(version_id) => format!("{}?versionId={}", source, encode_key(version_id)),
+        None => source,
+    }
+}
+
+// Reverses `encoding-type=url` on listing responses. S3 encodes spaces as `+`
+// there, a literal plus arrives as `%2B`.
+pub fn decode_key(encoded: &str) -> String {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = match bytes.get(i..i + 3) {
+            Some([b'%', high, low]) => hex_value(*high).zip(hex_value(*low)),
+            _ => None,
+        };
+
+        match (escaped, bytes[i]) {
+            (Some((high, low)), _) => {
+                decoded.push((high << 4) | low);
+                i += 3;
+                continue;
+            }
+            (None, b'+') => decoded.push(b' '),
+            (None, byte) => decoded.push(byte),
+        }
+
+        i += 1;
+    }
+
+    String::from_utf8(decoded).unwrap_or_else(|_| encoded.to_string())
+}
+
+fn hex_value(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|value| value as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_key_escapes_everything_but_unreserved_characters() {
+        assert_eq!(encode_key("a-b_c.d~e/f"), "a-b_c.d~e/f");
+        assert_eq!(encode_key("my file+1.txt"), "my%20file%2B1.txt");
+        assert_eq!(encode_key("café.png"), "caf%C3%A9.png");
+        assert_eq!(encode_key("a\tb\u{1}"), "a%09b%01");
+    }
+
+    #[test]
+    fn copy_source_encodes_key_and_version() {
+        assert_eq!(
+            copy_source("bucket", "a b/c+d.txt", None),
+            "bucket/a%20b/c%2Bd.txt"
+        );
+        assert_eq!(
+            copy_source("bucket", "key", Some("v1+/=")),
+            "bucket/key?versionId=v1%2B/%3D"
+        );
+    }
+
+    #[test]
+    fn object_url_prefers_the_public_base_url() {
+        assert_eq!(
+            object_url("https://s3.example.com/bucket", None, "a b.txt"),
+            "https://s3.example.com/bucket/a%20b.txt"
+        );
+        assert_eq!(
+            object_url(
+                "https://s3.example.com/bucket",
+                Some("https://cdn.example.com/"),
+                "a.txt"
+            ),
+            "https://cdn.example.com/a.txt"
+        );
+        assert_eq!(
+            object_url("https://s3.example.com/bucket", Some(""), "a.txt"),
+            "https://s3.example.com/bucket/a.txt"
+        );
+    }
+
+    #[test]
+    fn decode_key_reads_listing_encoding() {
+        assert_eq!(decode_key("my+file%2B1.txt"), "my file+1.txt");
+        assert_eq!(decode_key("caf%C3%A9.png"), "café.png");
+        assert_eq!(decode_key("caf%c3%a9.png"), "café.png");
+        // Incomplete escapes are kept as they are
+        assert_eq!(decode_key("100%"), "100%");
+        assert_eq!(decode_key("%zz"), "%zz");
+    }
+
+    #[test]
+    fn keys_survive_an_encode_decode_round_trip() {
+        let keys = [
+            "plain/key.txt",
+            "with space/and+plus",
+            "percent%20literal",
+            "unicode/日本語/😀.png",
+            "control\u{1}\u{7f}\r\n",
+            "trailing/",
+        ];
+
+        for key in keys {
+            assert_eq!(decode_key(&encode_key(key)), key);
+        }
+    }
+}
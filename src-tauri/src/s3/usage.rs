@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::cache::Mutation;
+use super::{ObjectInfo, ObjectKind};
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct StorageClassUsage {
+    pub storage_class: String,
+    pub total_size: i64,
+    pub object_count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct FolderSize {
+    pub prefix: String,
+    pub total_size: i64,
+    pub object_count: u64,
+    pub storage_classes: Vec<StorageClassUsage>,
+    pub calculated_at: String,
+}
+
+#[derive(Default)]
+pub struct UsageTotals {
+    pub total_size: i64,
+    pub object_count: u64,
+    by_storage_class: BTreeMap<String, StorageClassUsage>,
+}
+
+impl UsageTotals {
+    pub fn add(&mut self, object: &ObjectInfo) {
+        // Folder markers are real objects and count, implied prefixes don't
+        if object.kind == ObjectKind::Prefix {
+            return;
+        }
+
+        let size = object.size.unwrap_or(0);
+        // Providers omit the storage class for objects in the default tier
+        let storage_class = object
+            .storage_class
+            .clone()
+            .unwrap_or_else(|| "STANDARD".to_string());
+
+        self.total_size += size;
+        self.object_count += 1;
+
+        let usage = self
+            .by_storage_class
+            .entry(storage_class.clone())
+            .or_insert_with(|| StorageClassUsage {
+                storage_class,
+                total_size: 0,
+                object_count: 0,
+            });
+
+        usage.total_size += size;
+        usage.object_count += 1;
+    }
+
+    pub fn storage_classes(&self) -> Vec<StorageClassUsage> {
+        self.by_storage_class.values().cloned().collect()
+    }
+}
+
+// Folder prefixes always carry a trailing slash, except for the bucket root.
+pub fn normalize_prefix(prefix: Option<&str>) -> String {
+    match prefix {
+        Some(p) if !p.is_empty() && !p.ends_with('/') => format!("{}/", p),
+        Some(p) => p.to_string(),
+        None => String::new(),
+    }
+}
+
+type FolderSizeKey = (String, String, String);
+
+#[derive(Default)]
+struct FolderSizeEntries {
+    entries: HashMap<FolderSizeKey, FolderSize>,
+    // Bumped on every invalidation so a calculation that raced with a
+    // mutation doesn't store a stale result.
+    generation: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct FolderSizeCache {
+    inner: Arc<Mutex<FolderSizeEntries>>,
+}
+
+impl FolderSizeCache {
+    pub async fn get(
+        &self,
+        connection_id: &str,
+        bucket_name: &str,
+        prefix: &str,
+    ) -> Option<FolderSize> {
+        let key = (
+            connection_id.to_string(),
+            bucket_name.to_string(),
+            prefix.to_string(),
+        );
+
+        self.inner.lock().await.entries.get(&key).cloned()
+    }
+
+    pub async fn generation(&self) -> u64 {
+        self.inner.lock().await.generation
+    }
+
+    pub async fn insert(
+        &self,
+        connection_id: &str,
+        bucket_name: &str,
+        size: FolderSize,
+        generation: u64,
+    ) {
+        let mut inner = self.inner.lock().await;
+
+        if inner.generation != generation {
+            return;
+        }
+
+        let key = (
+            connection_id.to_string(),
+            bucket_name.to_string(),
+            size.prefix.clone(),
+        );
+
+        inner.entries.insert(key, size);
+    }
+
+    pub async fn invalidate(&self, connection_id: &str, bucket_name: &str, mutation: &Mutation) {
+        let mut inner = self.inner.lock().await;
+
+        inner.generation += 1;
+        inner.entries.retain(|(conn, bucket, prefix), _| {
+            conn != connection_id || bucket != bucket_name || !mutation.affects(prefix)
+        });
+    }
+}
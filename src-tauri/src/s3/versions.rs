@@ -0,0 +1,200 @@
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct ObjectVersion {
+    pub key: String,
+    pub version_id: String,
+    pub is_latest: bool,
+    pub is_delete_marker: bool,
+    pub size: Option<i64>,
+    pub last_modified: Option<String>,
+    pub etag: Option<String>,
+    pub storage_class: Option<String>,
+}
+
+pub struct VersionPage {
+    pub versions: Vec<ObjectVersion>,
+    pub next_key_marker: Option<String>,
+    pub next_version_id_marker: Option<String>,
+}
+
+// The version history of one key, reduced to what matters for point-in-time
+// browsing: the current version and the version that was current at `at`.
+// Either is `None` when the key didn't exist or was deleted at that time.
+#[derive(Debug, Clone)]
+pub struct KeyState {
+    pub key: String,
+    pub latest: Option<ObjectVersion>,
+    pub at: Option<ObjectVersion>,
+    resolved: bool,
+}
+
+impl KeyState {
+    fn new(key: String) -> Self {
+        KeyState {
+            key,
+            latest: None,
+            at: None,
+            resolved: false,
+        }
+    }
+}
+
+// Consumes versions in listing order (by key, newest first) and yields one
+// `KeyState` per key.
+pub struct PointInTime {
+    at: DateTime,
+    current: Option<KeyState>,
+}
+
+impl PointInTime {
+    pub fn new(at: DateTime) -> Self {
+        PointInTime { at, current: None }
+    }
+
+    // Returns the state of the previous key once the versions of a new key start
+    pub fn push(&mut self, version: ObjectVersion) -> Option<KeyState> {
+        let same_key = self
+            .current
+            .as_ref()
+            .is_some_and(|state| state.key == version.key);
+        let finished = if same_key {
+            None
+        } else {
+            self.current.replace(KeyState::new(version.key.clone()))
+        };
+
+        let state = self.current.as_mut()?;
+
+        if version.is_latest && !version.is_delete_marker {
+            state.latest = Some(version.clone());
+        }
+
+        if !state.resolved && is_before(&version, &self.at) {
+            state.resolved = true;
+
+            if !version.is_delete_marker {
+                state.at = Some(version);
+            }
+        }
+
+        finished
+    }
+
+    pub fn finish(self) -> Option<KeyState> {
+        self.current
+    }
+}
+
+fn is_before(version: &ObjectVersion, at: &DateTime) -> bool {
+    version
+        .last_modified
+        .as_deref()
+        .and_then(|date| DateTime::from_str(date, DateTimeFormat::DateTime).ok())
+        .is_some_and(|last_modified| last_modified <= *at)
+}
+
+pub fn parse_timestamp(timestamp: &str) -> Result<DateTime, String> {
+    DateTime::from_str(timestamp, DateTimeFormat::DateTime)
+        .map_err(|e| format!("Invalid timestamp '{}': {}", timestamp, e))
+}
+
+#[derive(Debug, Serialize, Deserialize, Type, Clone)]
+pub struct PointInTimeRestore {
+    // Keys an older version was copied back for
+    pub restored: Vec<String>,
+    // Keys that didn't exist at that time and got a delete marker
+    pub deleted: Vec<String>,
+    pub unchanged: u64,
+    pub errors: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::test_support::{delete_marker, version};
+
+    fn states(at: &str, versions: Vec<ObjectVersion>) -> Vec<KeyState> {
+        let mut point_in_time = PointInTime::new(parse_timestamp(at).unwrap());
+        let mut states: Vec<KeyState> = versions
+            .into_iter()
+            .filter_map(|version| point_in_time.push(version))
+            .collect();
+        states.extend(point_in_time.finish());
+        states
+    }
+
+    fn version_id(version: &Option<ObjectVersion>) -> Option<&str> {
+        version.as_ref().map(|version| version.version_id.as_str())
+    }
+
+    #[test]
+    fn picks_the_version_current_at_the_time() {
+        let states = states(
+            "2024-02-15T00:00:00Z",
+            vec![
+                version("a", "a3", "2024-03-01T00:00:00Z", true),
+                version("a", "a2", "2024-02-01T00:00:00Z", false),
+                version("a", "a1", "2024-01-01T00:00:00Z", false),
+            ],
+        );
+
+        assert_eq!(states.len(), 1);
+        assert_eq!(version_id(&states[0].latest), Some("a3"));
+        assert_eq!(version_id(&states[0].at), Some("a2"));
+    }
+
+    #[test]
+    fn version_written_at_the_exact_time_counts() {
+        let states = states(
+            "2024-02-01T00:00:00Z",
+            vec![
+                version("a", "a2", "2024-02-01T00:00:00Z", true),
+                version("a", "a1", "2024-01-01T00:00:00Z", false),
+            ],
+        );
+
+        assert_eq!(version_id(&states[0].at), Some("a2"));
+    }
+
+    #[test]
+    fn keys_created_later_or_deleted_at_the_time_have_no_version() {
+        let states = states(
+            "2024-02-15T00:00:00Z",
+            vec![
+                version("a", "a1", "2024-03-01T00:00:00Z", true),
+                version("b", "b2", "2024-03-01T00:00:00Z", true),
+                delete_marker("b", "b1", "2024-02-01T00:00:00Z", false),
+                version("b", "b0", "2024-01-01T00:00:00Z", false),
+            ],
+        );
+
+        assert_eq!(states.len(), 2);
+        assert_eq!(states[0].key, "a");
+        assert_eq!(version_id(&states[0].at), None);
+        assert_eq!(states[1].key, "b");
+        assert_eq!(version_id(&states[1].latest), Some("b2"));
+        assert_eq!(version_id(&states[1].at), None);
+    }
+
+    #[test]
+    fn deleted_keys_have_no_latest_version() {
+        let states = states(
+            "2024-02-15T00:00:00Z",
+            vec![
+                delete_marker("a", "a2", "2024-03-01T00:00:00Z", true),
+                version("a", "a1", "2024-01-01T00:00:00Z", false),
+            ],
+        );
+
+        assert_eq!(version_id(&states[0].latest), None);
+        assert_eq!(version_id(&states[0].at), Some("a1"));
+    }
+
+    #[test]
+    fn nothing_pushed_yields_nothing() {
+        assert!(states("2024-02-15T00:00:00Z", Vec::new()).is_empty());
+    }
+}
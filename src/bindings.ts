@@ -21,6 +21,30 @@ async listBuckets(connection: Connection) : Promise<Result<BucketInfo[], string>
     else return { status: "error", error: e  as any };
 }
 },
+async createBucket(opts: CreateBucketOptions) : Promise<Result<BucketInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_bucket", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteBucket(opts: DeleteBucketOptions) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_bucket", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async emptyAndDeleteBucket(opts: EmptyAndDeleteBucketOptions) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("empty_and_delete_bucket", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async listObjects(opts: ListObjectsOptions) : Promise<Result<ObjectInfo[], string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("list_objects", { opts }) };
@@ -29,6 +53,246 @@ async listObjects(opts: ListObjectsOptions) : Promise<Result<ObjectInfo[], strin
     else return { status: "error", error: e  as any };
 }
 },
+async setListingCacheTtl(ttlSeconds: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_listing_cache_ttl", { ttlSeconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearListingCache(opts: ClearListingCacheOptions) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_listing_cache", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listObjectsJob(opts: ListObjectsJobOptions) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_objects_job", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cancelJob(jobId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_job", { jobId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async searchObjects(opts: SearchObjectsOptions) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_objects", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async calculateFolderSize(opts: FolderSizeOptions) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("calculate_folder_size", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async generateBucketReport(opts: BucketReportOptions) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_bucket_report", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportBucketReport(opts: ExportBucketReportOptions) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_bucket_report", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async findDuplicates(opts: FindDuplicatesOptions) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("find_duplicates", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteDuplicates(opts: DeleteDuplicatesOptions) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_duplicates", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async compareLocations(opts: CompareLocationsOptions) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("compare_locations", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportCompareReport(opts: ExportCompareReportOptions) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_compare_report", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async copyObjects(opts: CopyObjectsOptions) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("copy_objects", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async importInventory(opts: ImportInventoryOptions) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_inventory", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listInventories() : Promise<Result<InventoryInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_inventories") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteInventory(inventoryId: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_inventory", { inventoryId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listInventoryObjects(opts: ListInventoryObjectsOptions) : Promise<Result<InventoryListing, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_inventory_objects", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async searchInventory(opts: SearchInventoryOptions) : Promise<Result<InventorySearchResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_inventory", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async generateInventoryReport(opts: InventoryReportOptions) : Promise<Result<InventoryReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_inventory_report", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportListing(opts: ExportListingOptions) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_listing", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async indexBucket(opts: IndexBucketOptions) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("index_bucket", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listIndexedBuckets() : Promise<Result<IndexedBucket[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_indexed_buckets") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeIndexedBucket(opts: RemoveIndexedBucketOptions) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_indexed_bucket", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async pruneIndexedBuckets() : Promise<Result<IndexedBucket[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("prune_indexed_buckets") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async searchIndex(opts: SearchIndexOptions) : Promise<Result<IndexSearchResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_index", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getObjectDetails(opts: ObjectDetailsOptions) : Promise<Result<ObjectDetails, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_object_details", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateObjectMetadata(opts: UpdateObjectMetadataOptions) : Promise<Result<MetadataUpdate[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_object_metadata", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async presignGetUrls(opts: PresignGetOptions) : Promise<Result<PresignedUrl[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("presign_get_urls", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async presignPutUrl(opts: PresignPutOptions) : Promise<Result<PresignedUpload, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("presign_put_url", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async presignPostPolicy(opts: PresignPostOptions) : Promise<Result<PresignedPost, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("presign_post_policy", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async downloadObject(opts: DownloadObjectOptions) : Promise<Result<number[], string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("download_object", { opts }) };
@@ -93,6 +357,70 @@ async moveObjects(opts: MoveObjectsOptions) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async listObjectVersions(opts: ListObjectVersionsOptions) : Promise<Result<ObjectVersion[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_object_versions", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async downloadObjectVersion(opts: ObjectVersionOptions) : Promise<Result<number[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("download_object_version", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async restoreObjectVersion(opts: ObjectVersionOptions) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_object_version", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async undeleteObjects(opts: UndeleteObjectsOptions) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("undelete_objects", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listObjectsAt(opts: ListObjectsAtOptions) : Promise<Result<ObjectVersion[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_objects_at", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async restorePrefixToTime(opts: RestorePrefixToTimeOptions) : Promise<Result<PointInTimeRestore, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_prefix_to_time", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async purgeObjectVersions(opts: PurgeVersionsOptions) : Promise<Result<PurgeSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("purge_object_versions", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cleanupFolderMarkers(opts: CleanupFolderMarkersOptions) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cleanup_folder_markers", { opts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async saveConnection(config: ConnectionConfig) : Promise<Result<string, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("save_connection", { config }) };
@@ -138,6 +466,53 @@ async isConnectionDuplicate(config: ConnectionConfig) : Promise<Result<boolean,
 /** user-defined events **/
 
 
+export const events = __makeEvents__<{
+bucketReportFinished: BucketReportFinished,
+bucketReportProgress: BucketReportProgress,
+compareFinished: CompareFinished,
+compareProgress: CompareProgress,
+copyObjectsFinished: CopyObjectsFinished,
+copyObjectsProgress: CopyObjectsProgress,
+duplicateScanFinished: DuplicateScanFinished,
+duplicateScanProgress: DuplicateScanProgress,
+emptyBucketFinished: EmptyBucketFinished,
+emptyBucketProgress: EmptyBucketProgress,
+folderSizeFinished: FolderSizeFinished,
+folderSizeProgress: FolderSizeProgress,
+indexFinished: IndexFinished,
+indexProgress: IndexProgress,
+inventoryImportFinished: InventoryImportFinished,
+inventoryImportProgress: InventoryImportProgress,
+listObjectsBatch: ListObjectsBatch,
+listObjectsFinished: ListObjectsFinished,
+listingExportFinished: ListingExportFinished,
+listingExportProgress: ListingExportProgress,
+searchObjectsFinished: SearchObjectsFinished,
+searchObjectsMatches: SearchObjectsMatches
+}>({
+bucketReportFinished: "bucket-report-finished",
+bucketReportProgress: "bucket-report-progress",
+compareFinished: "compare-finished",
+compareProgress: "compare-progress",
+copyObjectsFinished: "copy-objects-finished",
+copyObjectsProgress: "copy-objects-progress",
+duplicateScanFinished: "duplicate-scan-finished",
+duplicateScanProgress: "duplicate-scan-progress",
+emptyBucketFinished: "empty-bucket-finished",
+emptyBucketProgress: "empty-bucket-progress",
+folderSizeFinished: "folder-size-finished",
+folderSizeProgress: "folder-size-progress",
+indexFinished: "index-finished",
+indexProgress: "index-progress",
+inventoryImportFinished: "inventory-import-finished",
+inventoryImportProgress: "inventory-import-progress",
+listObjectsBatch: "list-objects-batch",
+listObjectsFinished: "list-objects-finished",
+listingExportFinished: "listing-export-finished",
+listingExportProgress: "listing-export-progress",
+searchObjectsFinished: "search-objects-finished",
+searchObjectsMatches: "search-objects-matches"
+})
 
 /** user-defined constants **/
 
@@ -147,26 +522,126 @@ async isConnectionDuplicate(config: ConnectionConfig) : Promise<Result<boolean,
 
 export type BucketInfo = { provider: BucketProvider; name: string; region: string; endpoint_url: string; creation_date: string | null }
 export type BucketProvider = "S3" | "R2" | "Custom"
-export type CommonConfig = { label: string; access_key_id: string; secret_access_key: string }
+export type BucketReport = { bucket_name: string; prefix: string; generated_at: string; total_size: number; object_count: number; storage_classes: StorageClassUsage[]; largest_objects: ObjectInfo[]; size_histogram: HistogramBucket[]; age_histogram: HistogramBucket[]; top_level_prefixes: PrefixUsage[] }
+export type BucketReportFinished = { job_id: string; report: BucketReport | null; cancelled: boolean; error: string | null }
+export type BucketReportOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null; top_n: number | null }
+export type BucketReportProgress = { job_id: string; total_size: number; object_count: number }
+export type CleanupFolderMarkersOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null; dry_run: boolean }
+export type ClearListingCacheOptions = { connection: Connection; bucket_name: string | null }
+export type CommonConfig = { label: string; access_key_id: string; secret_access_key: string; public_base_url: string | null }
 export type CommonOperationOptions = { connection: Connection; bucket_region: string | null }
-export type Connection = { id: string; label: string; provider: BucketProvider }
+export type CompareEntry = { key: string; status: CompareStatus; left_size: number | null; right_size: number | null; left_etag: string | null; right_etag: string | null }
+export type CompareFinished = { job_id: string; report: CompareReport | null; cancelled: boolean; error: string | null }
+export type CompareLocationsOptions = { left: ObjectLocation; right: ObjectLocation; verify: boolean }
+export type CompareProgress = { job_id: string; left_count: number; right_count: number; hashed_objects: number }
+export type CompareReport = { left_bucket: string; left_prefix: string; right_bucket: string; right_prefix: string; left_count: number; right_count: number; identical_count: number; unverified_count: number; entries: CompareEntry[] }
+export type CompareStatus = "OnlyLeft" | "OnlyRight" | "SizeDiffers" | "EtagDiffers" | "ChecksumDiffers"
+export type Connection = { id: string; identity: string; label: string; provider: BucketProvider }
 export type ConnectionConfig = { S3: S3Config } | { R2: R2Config } | { Custom: CustomConfig }
+export type CopyObjectsFinished = { job_id: string; copied: number; errors: string[]; cancelled: boolean }
+export type CopyObjectsOptions = { source: ObjectLocation; destination: ObjectLocation; keys: string[] }
+export type CopyObjectsProgress = { job_id: string; copied: number; failed: number; total: number }
+export type CreateBucketOptions = { connection: Connection; bucket_name: string; region: string | null; object_lock: boolean; versioning: boolean; policy: string | null }
 export type CreateFolderOptions = { common: CommonOperationOptions; bucket_name: string; folder_key: string }
 export type CustomConfig = { common: CommonConfig; endpoint_url: string }
+export type DeleteBucketOptions = { common: CommonOperationOptions; bucket_name: string }
+export type DeleteDuplicatesOptions = { common: CommonOperationOptions; bucket_name: string; selections: DuplicateSelection[] }
 export type DeleteFolderOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string }
 export type DeleteObjectsOptions = { common: CommonOperationOptions; bucket_name: string; keys: string[] }
 export type DownloadFolderOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string }
 export type DownloadObjectOptions = { common: CommonOperationOptions; bucket_name: string; key: string }
 export type DownloadObjectsOptions = { common: CommonOperationOptions; bucket_name: string; keys: string[] }
-export type ListObjectsOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null }
+export type DuplicateGroup = { size: number; matched_by: DuplicateMatch; fingerprint: string; keys: string[] }
+export type DuplicateMatch = "Md5" | "MultipartEtag" | "Sha256"
+export type DuplicateReport = { bucket_name: string; prefix: string; scanned_objects: number; verified: boolean; groups: DuplicateGroup[]; reclaimable_bytes: number }
+export type DuplicateScanFinished = { job_id: string; report: DuplicateReport | null; cancelled: boolean; error: string | null }
+export type DuplicateScanProgress = { job_id: string; scanned_objects: number; hashed_objects: number }
+export type DuplicateSelection = { keep: string; keys: string[] }
+export type EmptyAndDeleteBucketOptions = { common: CommonOperationOptions; bucket_name: string; dry_run: boolean; confirmation: string | null }
+export type EmptyBucketFinished = { job_id: string; summary: EmptyBucketSummary; dry_run: boolean; bucket_deleted: boolean; errors: string[]; cancelled: boolean; error: string | null }
+export type EmptyBucketProgress = { job_id: string; summary: EmptyBucketSummary }
+export type EmptyBucketSummary = { versioned: boolean; object_count: number; noncurrent_version_count: number; delete_marker_count: number; total_size: number; multipart_upload_count: number }
+export type ExportBucketReportOptions = { report: BucketReport; format: ReportFormat; file_path: string }
+export type ExportCompareReportOptions = { report: CompareReport; format: ReportFormat; file_path: string }
+export type ExportListingOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null; format: ListingFormat; file_path: string }
+export type FindDuplicatesOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null; verify: boolean }
+export type FolderSize = { prefix: string; total_size: number; object_count: number; storage_classes: StorageClassUsage[]; calculated_at: string }
+export type FolderSizeFinished = { job_id: string; prefix: string; size: FolderSize | null; cached: boolean; cancelled: boolean; error: string | null }
+export type FolderSizeOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null; force_refresh: boolean }
+export type FolderSizeProgress = { job_id: string; prefix: string; total_size: number; object_count: number }
+export type HistogramBucket = { label: string; object_count: number; total_size: number }
+export type ImportInventoryOptions = { common: CommonOperationOptions; bucket_name: string; manifest_key: string }
+export type IndexBucketOptions = { common: CommonOperationOptions; bucket_name: string; full: boolean }
+export type IndexFinished = { job_id: string; bucket: IndexedBucket | null; cancelled: boolean; error: string | null }
+export type IndexMatch = { connection_id: string; bucket_name: string; object: ObjectInfo }
+export type IndexProgress = { job_id: string; bucket_name: string; object_count: number }
+export type IndexSearchResult = { matches: IndexMatch[]; truncated: boolean }
+export type IndexedBucket = { connection_id: string; bucket_name: string; indexed_at: string | null; object_count: number; total_size: number; pending_changes: number; needs_full_crawl: boolean }
+export type InventoryImportFinished = { job_id: string; inventory: InventoryInfo | null; cancelled: boolean; error: string | null }
+export type InventoryImportProgress = { job_id: string; files_done: number; files_total: number; object_count: number }
+export type InventoryInfo = { id: number; connection_id: string; bucket_name: string; manifest_bucket: string; manifest_key: string; inventory_date: string; imported_at: string; object_count: number; total_size: number }
+export type InventoryListing = { inventory: InventoryInfo; objects: ObjectInfo[]; next_start_after: string | null }
+export type InventoryReport = { inventory: InventoryInfo; report: BucketReport }
+export type InventoryReportOptions = { inventory_id: number; prefix: string | null; top_n: number | null }
+export type InventorySearchResult = { inventory: InventoryInfo; objects: ObjectInfo[]; truncated: boolean }
+export type KeyPattern = { Substring: string } | { Glob: string } | { Regex: string }
+export type ListInventoryObjectsOptions = { inventory_id: number; prefix: string | null; delimiter: string | null; start_after: string | null; max_keys: number | null }
+export type ListObjectVersionsOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null }
+export type ListObjectsAtOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null; timestamp: string }
+export type ListObjectsBatch = { job_id: string; objects: ObjectInfo[] }
+export type ListObjectsFinished = { job_id: string; count: number; cancelled: boolean; error: string | null }
+export type ListObjectsJobOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null; recursive: boolean }
+export type ListObjectsOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null; delimiter: string | null; flat: boolean }
+export type ListingExportFinished = { job_id: string; object_count: number; cancelled: boolean; error: string | null }
+export type ListingExportProgress = { job_id: string; object_count: number }
+export type ListingFormat = "Csv" | "JsonLines" | "Parquet"
+export type MetadataChanges = { content_type: string | null; cache_control: string | null; content_disposition: string | null; content_encoding: string | null; content_language: string | null; expires: string | null; set_metadata: Partial<{ [key in string]: string }>; remove_metadata: string[] }
+export type MetadataTarget = { Object: string } | { Prefix: string }
+export type MetadataUpdate = { key: string; before: ObjectHeaders | null; after: ObjectHeaders | null; error: string | null }
 export type MoveObjectsOptions = { common: CommonOperationOptions; bucket_name: string; keys: string[]; destination_prefix: string }
-export type ObjectInfo = { key: string; size: number | null; last_modified: string | null; storage_class: string | null; is_folder: boolean; url: string }
+export type ObjectChecksum = { algorithm: string; value: string }
+export type ObjectDetails = { key: string; version_id: string | null; content_type: string | null; content_length: number | null; etag: string | null; last_modified: string | null; storage_class: string | null; cache_control: string | null; content_disposition: string | null; content_encoding: string | null; content_language: string | null; expires: string | null; website_redirect_location: string | null; metadata: Partial<{ [key in string]: string }>; server_side_encryption: string | null; sse_kms_key_id: string | null; sse_customer_algorithm: string | null; bucket_key_enabled: boolean | null; object_lock_mode: string | null; object_lock_retain_until_date: string | null; object_lock_legal_hold_status: string | null; replication_status: string | null; restore_status: RestoreStatus | null; checksums: ObjectChecksum[]; parts_count: number | null; tags: ObjectTag[] | null }
+export type ObjectDetailsOptions = { common: CommonOperationOptions; bucket_name: string; key: string; version_id: string | null }
+export type ObjectHeaders = { content_type: string | null; cache_control: string | null; content_disposition: string | null; content_encoding: string | null; content_language: string | null; expires: string | null; metadata: Partial<{ [key in string]: string }> }
+export type ObjectInfo = { key: string; size: number | null; last_modified: string | null; storage_class: string | null; is_folder: boolean; kind: ObjectKind; url: string; etag: string | null; checksum_algorithms: string[]; owner: ObjectOwner | null; restore_status: RestoreStatus | null }
+export type ObjectKind = "File" | "Prefix" | "FolderMarker"
+export type ObjectLocation = { common: CommonOperationOptions; bucket_name: string; prefix: string | null }
+export type ObjectOwner = { id: string | null; display_name: string | null }
+export type ObjectTag = { key: string; value: string }
+export type ObjectVersion = { key: string; version_id: string; is_latest: boolean; is_delete_marker: boolean; size: number | null; last_modified: string | null; etag: string | null; storage_class: string | null }
+export type ObjectVersionOptions = { common: CommonOperationOptions; bucket_name: string; key: string; version_id: string }
+export type PointInTimeRestore = { restored: string[]; deleted: string[]; unchanged: number; errors: string[] }
+export type PostFormField = { name: string; value: string }
+export type PrefixUsage = { prefix: string; object_count: number; total_size: number }
+export type PresignGetOptions = { common: CommonOperationOptions; bucket_name: string; keys: string[]; expires_in_seconds: number; overrides: ResponseOverrides }
+export type PresignPostOptions = { common: CommonOperationOptions; bucket_name: string; key_prefix: string; allow_any_key: boolean; min_size: number; max_size: number; content_type: string | null; expires_in_seconds: number }
+export type PresignPutOptions = { common: CommonOperationOptions; bucket_name: string; key: string; expires_in_seconds: number; content_type: string | null }
+export type PresignedPost = { url: string; fields: PostFormField[]; expires_at: string; curl_command: string }
+export type PresignedUpload = { key: string; url: string; content_type: string | null; expires_at: string; curl_command: string }
+export type PresignedUrl = { key: string; url: string; expires_at: string }
+export type PurgeRules = { noncurrent_older_than_days: number | null; keep_noncurrent_versions: number | null; remove_orphaned_delete_markers: boolean }
+export type PurgeSummary = { keys_affected: number; versions_deleted: number; delete_markers_deleted: number; bytes_reclaimed: number; errors: string[] }
+export type PurgeVersionsOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string; rules: PurgeRules; dry_run: boolean }
 export type R2Config = { common: CommonConfig; account_id: string }
+export type RemoveIndexedBucketOptions = { connection_id: string; bucket_name: string }
+export type ReportFormat = "Json" | "Csv"
+export type ResponseOverrides = { content_disposition: string | null; content_type: string | null; cache_control: string | null; content_language: string | null }
+export type RestorePrefixToTimeOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string; timestamp: string; dry_run: boolean }
+export type RestoreStatus = { is_restore_in_progress: boolean; restore_expiry_date: string | null }
 export type S3Config = { common: CommonConfig }
 export type SavedConnectionConfig = { S3: SavedS3Config } | { R2: SavedR2Config } | { Custom: SavedCustomConfig }
 export type SavedCustomConfig = { common: CommonConfig; endpoint_url: string; uuid: string }
 export type SavedR2Config = { common: CommonConfig; account_id: string; uuid: string }
 export type SavedS3Config = { common: CommonConfig; uuid: string }
+export type SearchFilters = { pattern: KeyPattern | null; case_sensitive: boolean; min_size: number | null; max_size: number | null; modified_after: string | null; modified_before: string | null; storage_classes: string[]; extensions: string[] }
+export type SearchIndexOptions = { filters: SearchFilters; delimiter: string | null; max_results: number | null }
+export type SearchInventoryOptions = { inventory_id: number; prefix: string | null; delimiter: string | null; filters: SearchFilters; max_results: number | null }
+export type SearchObjectsFinished = { job_id: string; scanned: number; matched: number; truncated: boolean; cancelled: boolean; error: string | null }
+export type SearchObjectsMatches = { job_id: string; objects: ObjectInfo[] }
+export type SearchObjectsOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null; filters: SearchFilters; max_scanned: number | null }
+export type StorageClassUsage = { storage_class: string; total_size: number; object_count: number }
+export type UndeleteObjectsOptions = { common: CommonOperationOptions; bucket_name: string; keys: string[] }
+export type UpdateObjectMetadataOptions = { common: CommonOperationOptions; bucket_name: string; target: MetadataTarget; changes: MetadataChanges; dry_run: boolean }
 export type UploadObjectsOptions = { common: CommonOperationOptions; bucket_name: string; prefix: string | null; file_paths: string[] }
 
 /** tauri-specta globals **/
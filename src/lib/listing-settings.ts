@@ -0,0 +1,66 @@
+import { BucketInfo, Connection } from "@/bindings";
+import { useState } from "react";
+
+export interface ListingSettings {
+  delimiter: string;
+  flat: boolean;
+}
+
+export const DELIMITERS = ["/", ":", "|"] as const;
+
+const DEFAULT_LISTING_SETTINGS: ListingSettings = {
+  delimiter: "/",
+  flat: false,
+};
+
+// Bucket names are only unique per connection, and the endpoint URL of a
+// bucket can change, e.g. once its region is known. The connection id is new
+// on every connect, its identity stays the same across sessions.
+function getStorageKey(connection: Connection, bucket: BucketInfo) {
+  return `nicebucket_listing_settings_${connection.identity}_${bucket.name}`;
+}
+
+function loadListingSettings(
+  connection: Connection,
+  bucket: BucketInfo,
+): ListingSettings {
+  const stored = localStorage.getItem(getStorageKey(connection, bucket));
+
+  if (!stored) {
+    return DEFAULT_LISTING_SETTINGS;
+  }
+
+  try {
+    const parsed = JSON.parse(stored) as Partial<ListingSettings>;
+
+    return {
+      delimiter: parsed.delimiter || DEFAULT_LISTING_SETTINGS.delimiter,
+      flat: parsed.flat ?? DEFAULT_LISTING_SETTINGS.flat,
+    };
+  } catch {
+    localStorage.removeItem(getStorageKey(connection, bucket));
+    return DEFAULT_LISTING_SETTINGS;
+  }
+}
+
+export function useListingSettings(
+  connection: Connection,
+  bucket: BucketInfo,
+) {
+  // Settings are read on every render so switching buckets picks up the right ones
+  const [, setRevision] = useState(0);
+  const listingSettings = loadListingSettings(connection, bucket);
+
+  const setListingSettings = (settings: ListingSettings) => {
+    localStorage.setItem(
+      getStorageKey(connection, bucket),
+      JSON.stringify(settings),
+    );
+    setRevision((revision) => revision + 1);
+  };
+
+  return {
+    listingSettings,
+    setListingSettings,
+  };
+}
@@ -1,15 +1,49 @@
 import {
+  BucketReportOptions,
+  CleanupFolderMarkersOptions,
+  ClearListingCacheOptions,
+  CompareLocationsOptions,
   Connection,
   ConnectionConfig,
+  CopyObjectsOptions,
+  CreateBucketOptions,
   CreateFolderOptions,
+  DeleteBucketOptions,
+  DeleteDuplicatesOptions,
   DeleteFolderOptions,
   DeleteObjectsOptions,
   DownloadFolderOptions,
   DownloadObjectOptions,
   DownloadObjectsOptions,
+  EmptyAndDeleteBucketOptions,
+  ExportBucketReportOptions,
+  ExportCompareReportOptions,
+  ExportListingOptions,
+  FindDuplicatesOptions,
+  FolderSizeOptions,
+  ImportInventoryOptions,
+  IndexBucketOptions,
+  InventoryReportOptions,
+  ListInventoryObjectsOptions,
+  ListObjectVersionsOptions,
+  ListObjectsAtOptions,
+  ListObjectsJobOptions,
   ListObjectsOptions,
   MoveObjectsOptions,
+  ObjectDetailsOptions,
+  ObjectVersionOptions,
+  PresignGetOptions,
+  PresignPostOptions,
+  PresignPutOptions,
+  PurgeVersionsOptions,
+  RemoveIndexedBucketOptions,
+  RestorePrefixToTimeOptions,
   Result,
+  SearchIndexOptions,
+  SearchInventoryOptions,
+  SearchObjectsOptions,
+  UndeleteObjectsOptions,
+  UpdateObjectMetadataOptions,
   UploadObjectsOptions,
   commands as tauriCommands,
 } from "@/bindings";
@@ -57,11 +91,176 @@ export function useCommands(): { commands: CommandMap } {
     return unwrap(result);
   }
 
+  async function createBucket(opts: CreateBucketOptions) {
+    const result = tauriCommands.createBucket(opts);
+    return unwrap(result);
+  }
+
+  async function deleteBucket(opts: DeleteBucketOptions) {
+    const result = tauriCommands.deleteBucket(opts);
+    return unwrap(result);
+  }
+
+  async function emptyAndDeleteBucket(opts: EmptyAndDeleteBucketOptions) {
+    const result = tauriCommands.emptyAndDeleteBucket(opts);
+    return unwrap(result);
+  }
+
   async function listObjects(opts: ListObjectsOptions) {
     const result = tauriCommands.listObjects(opts);
     return unwrap(result);
   }
 
+  async function setListingCacheTtl(ttlSeconds: number) {
+    const result = tauriCommands.setListingCacheTtl(ttlSeconds);
+    return unwrap(result);
+  }
+
+  async function clearListingCache(opts: ClearListingCacheOptions) {
+    const result = tauriCommands.clearListingCache(opts);
+    return unwrap(result);
+  }
+
+  async function listObjectsJob(opts: ListObjectsJobOptions) {
+    const result = tauriCommands.listObjectsJob(opts);
+    return unwrap(result);
+  }
+
+  async function cancelJob(jobId: string) {
+    const result = tauriCommands.cancelJob(jobId);
+    return unwrap(result);
+  }
+
+  async function searchObjects(opts: SearchObjectsOptions) {
+    const result = tauriCommands.searchObjects(opts);
+    return unwrap(result);
+  }
+
+  async function calculateFolderSize(opts: FolderSizeOptions) {
+    const result = tauriCommands.calculateFolderSize(opts);
+    return unwrap(result);
+  }
+
+  async function generateBucketReport(opts: BucketReportOptions) {
+    const result = tauriCommands.generateBucketReport(opts);
+    return unwrap(result);
+  }
+
+  async function exportBucketReport(opts: ExportBucketReportOptions) {
+    const result = tauriCommands.exportBucketReport(opts);
+    return unwrap(result);
+  }
+
+  async function findDuplicates(opts: FindDuplicatesOptions) {
+    const result = tauriCommands.findDuplicates(opts);
+    return unwrap(result);
+  }
+
+  async function deleteDuplicates(opts: DeleteDuplicatesOptions) {
+    const result = tauriCommands.deleteDuplicates(opts);
+    return unwrap(result);
+  }
+
+  async function compareLocations(opts: CompareLocationsOptions) {
+    const result = tauriCommands.compareLocations(opts);
+    return unwrap(result);
+  }
+
+  async function exportCompareReport(opts: ExportCompareReportOptions) {
+    const result = tauriCommands.exportCompareReport(opts);
+    return unwrap(result);
+  }
+
+  async function copyObjects(opts: CopyObjectsOptions) {
+    const result = tauriCommands.copyObjects(opts);
+    return unwrap(result);
+  }
+
+  async function importInventory(opts: ImportInventoryOptions) {
+    const result = tauriCommands.importInventory(opts);
+    return unwrap(result);
+  }
+
+  async function listInventories() {
+    const result = tauriCommands.listInventories();
+    return unwrap(result);
+  }
+
+  async function deleteInventory(inventoryId: number) {
+    const result = tauriCommands.deleteInventory(inventoryId);
+    return unwrap(result);
+  }
+
+  async function listInventoryObjects(opts: ListInventoryObjectsOptions) {
+    const result = tauriCommands.listInventoryObjects(opts);
+    return unwrap(result);
+  }
+
+  async function searchInventory(opts: SearchInventoryOptions) {
+    const result = tauriCommands.searchInventory(opts);
+    return unwrap(result);
+  }
+
+  async function generateInventoryReport(opts: InventoryReportOptions) {
+    const result = tauriCommands.generateInventoryReport(opts);
+    return unwrap(result);
+  }
+
+  async function exportListing(opts: ExportListingOptions) {
+    const result = tauriCommands.exportListing(opts);
+    return unwrap(result);
+  }
+
+  async function indexBucket(opts: IndexBucketOptions) {
+    const result = tauriCommands.indexBucket(opts);
+    return unwrap(result);
+  }
+
+  async function listIndexedBuckets() {
+    const result = tauriCommands.listIndexedBuckets();
+    return unwrap(result);
+  }
+
+  async function removeIndexedBucket(opts: RemoveIndexedBucketOptions) {
+    const result = tauriCommands.removeIndexedBucket(opts);
+    return unwrap(result);
+  }
+
+  async function pruneIndexedBuckets() {
+    const result = tauriCommands.pruneIndexedBuckets();
+    return unwrap(result);
+  }
+
+  async function searchIndex(opts: SearchIndexOptions) {
+    const result = tauriCommands.searchIndex(opts);
+    return unwrap(result);
+  }
+
+  async function getObjectDetails(opts: ObjectDetailsOptions) {
+    const result = tauriCommands.getObjectDetails(opts);
+    return unwrap(result);
+  }
+
+  async function updateObjectMetadata(opts: UpdateObjectMetadataOptions) {
+    const result = tauriCommands.updateObjectMetadata(opts);
+    return unwrap(result);
+  }
+
+  async function presignGetUrls(opts: PresignGetOptions) {
+    const result = tauriCommands.presignGetUrls(opts);
+    return unwrap(result);
+  }
+
+  async function presignPutUrl(opts: PresignPutOptions) {
+    const result = tauriCommands.presignPutUrl(opts);
+    return unwrap(result);
+  }
+
+  async function presignPostPolicy(opts: PresignPostOptions) {
+    const result = tauriCommands.presignPostPolicy(opts);
+    return unwrap(result);
+  }
+
   async function downloadObject(opts: DownloadObjectOptions) {
     const result = tauriCommands.downloadObject(opts);
     return unwrap(result);
@@ -102,6 +301,46 @@ export function useCommands(): { commands: CommandMap } {
     return unwrap(result);
   }
 
+  async function listObjectVersions(opts: ListObjectVersionsOptions) {
+    const result = tauriCommands.listObjectVersions(opts);
+    return unwrap(result);
+  }
+
+  async function downloadObjectVersion(opts: ObjectVersionOptions) {
+    const result = tauriCommands.downloadObjectVersion(opts);
+    return unwrap(result);
+  }
+
+  async function restoreObjectVersion(opts: ObjectVersionOptions) {
+    const result = tauriCommands.restoreObjectVersion(opts);
+    return unwrap(result);
+  }
+
+  async function undeleteObjects(opts: UndeleteObjectsOptions) {
+    const result = tauriCommands.undeleteObjects(opts);
+    return unwrap(result);
+  }
+
+  async function listObjectsAt(opts: ListObjectsAtOptions) {
+    const result = tauriCommands.listObjectsAt(opts);
+    return unwrap(result);
+  }
+
+  async function restorePrefixToTime(opts: RestorePrefixToTimeOptions) {
+    const result = tauriCommands.restorePrefixToTime(opts);
+    return unwrap(result);
+  }
+
+  async function purgeObjectVersions(opts: PurgeVersionsOptions) {
+    const result = tauriCommands.purgeObjectVersions(opts);
+    return unwrap(result);
+  }
+
+  async function cleanupFolderMarkers(opts: CleanupFolderMarkersOptions) {
+    const result = tauriCommands.cleanupFolderMarkers(opts);
+    return unwrap(result);
+  }
+
   async function saveConnection(config: ConnectionConfig) {
     const result = tauriCommands.saveConnection(config);
     return unwrap(result);
@@ -130,7 +369,40 @@ export function useCommands(): { commands: CommandMap } {
   const commands: CommandMap = {
     connectToS3,
     listBuckets,
+    createBucket,
+    deleteBucket,
+    emptyAndDeleteBucket,
     listObjects,
+    setListingCacheTtl,
+    clearListingCache,
+    listObjectsJob,
+    cancelJob,
+    searchObjects,
+    calculateFolderSize,
+    generateBucketReport,
+    exportBucketReport,
+    findDuplicates,
+    deleteDuplicates,
+    compareLocations,
+    exportCompareReport,
+    copyObjects,
+    importInventory,
+    listInventories,
+    deleteInventory,
+    listInventoryObjects,
+    searchInventory,
+    generateInventoryReport,
+    exportListing,
+    indexBucket,
+    listIndexedBuckets,
+    removeIndexedBucket,
+    pruneIndexedBuckets,
+    searchIndex,
+    getObjectDetails,
+    updateObjectMetadata,
+    presignGetUrls,
+    presignPutUrl,
+    presignPostPolicy,
     downloadObject,
     downloadObjects,
     deleteObjects,
@@ -139,6 +411,14 @@ export function useCommands(): { commands: CommandMap } {
     uploadObjects,
     createFolder,
     moveObjects,
+    listObjectVersions,
+    downloadObjectVersion,
+    restoreObjectVersion,
+    undeleteObjects,
+    listObjectsAt,
+    restorePrefixToTime,
+    purgeObjectVersions,
+    cleanupFolderMarkers,
     saveConnection,
     loadSavedConnections,
     deleteSavedConnection,
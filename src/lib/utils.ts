@@ -23,8 +23,12 @@ export function formatFileSize({ bytes }: { bytes: number }) {
   const units = ["B", "kB", "MB", "GB", "TB", "PB"];
   const base = 1024;
 
-  const exponent = Math.floor(Math.log(bytes) / Math.log(base));
-  const unitIndex = Math.min(exponent, units.length - 1);
+  // Step through the units instead of using logarithms, so values exactly at
+  // a unit boundary (e.g. 1 GiB) are never rounded down into the smaller unit
+  let unitIndex = 0;
+  while (unitIndex < units.length - 1 && bytes >= base ** (unitIndex + 1)) {
+    unitIndex++;
+  }
   const formattedValue = bytes / base ** unitIndex;
   const unit = units[unitIndex];
 
@@ -58,3 +62,15 @@ export function isTextFile(fileName: string) {
 export function formatStorageClass(storageClass: string) {
   return STORAGE_CLASSES[storageClass] ?? null;
 }
+
+/**
+ * Keys may contain control characters, which are invisible or break the layout.
+ * They are shown as their Unicode control pictures instead, e.g. a newline becomes ␊.
+ */
+export function visibleKey(key: string) {
+  // eslint-disable-next-line no-control-regex
+  return key.replace(/[\u0000-\u001f\u007f]/g, (char) => {
+    const code = char.charCodeAt(0);
+    return String.fromCharCode(code === 0x7f ? 0x2421 : 0x2400 + code);
+  });
+}
@@ -12,14 +12,19 @@ import { copyToClipboard } from "@/lib/actions";
 import { useCommands } from "@/lib/use-commands";
 import { skipToken, useQuery } from "@tanstack/react-query";
 import { EllipsisVertical, Folder } from "lucide-react";
+import { useState } from "react";
 import { toast } from "sonner";
+import { DeleteBucketDialog } from "./delete-bucket-dialog";
 import { useDashboardContext } from "./use-dashboard-context";
 
 export function BucketList() {
   const { commands } = useCommands();
   const { connection, setSelectedBucket, searchPhrase } = useDashboardContext();
+  const [bucketToDelete, setBucketToDelete] = useState<BucketInfo | null>(
+    null,
+  );
 
-  const { data, isPending, isError } = useQuery({
+  const { data, isPending, isError, refetch } = useQuery({
     queryKey: ["listBuckets", connection?.id],
 
     queryFn: connection
@@ -95,6 +100,15 @@ export function BucketList() {
                       Copy Bucket Url
                       <DropdownMenuShortcut>c</DropdownMenuShortcut>
                     </DropdownMenuItem>
+                    <DropdownMenuItem
+                      onClick={(e) => {
+                        e.stopPropagation();
+
+                        setBucketToDelete(bucket);
+                      }}
+                    >
+                      Delete Bucket
+                    </DropdownMenuItem>
                   </DropdownMenuContent>
                 </DropdownMenu>
               </div>
@@ -102,6 +116,22 @@ export function BucketList() {
           );
         }}
       </FileTree>
+
+      {connection && bucketToDelete && (
+        <DeleteBucketDialog
+          isOpen={!!bucketToDelete}
+          onOpenChange={(open) => {
+            if (!open) {
+              setBucketToDelete(null);
+            }
+          }}
+          onDeleted={() => {
+            void refetch();
+          }}
+          connection={connection}
+          bucket={bucketToDelete}
+        />
+      )}
     </div>
   );
 }
@@ -99,6 +99,7 @@ export function CredentialsForm({ className }: CredentialsFormProps) {
 
       r2AccountId: z.string().optional(),
       endpointUrl: z.string().optional(),
+      publicBaseUrl: z.string().url().optional().or(z.literal("")),
     })
     .refine(
       (args) => {
@@ -160,7 +161,7 @@ export function CredentialsForm({ className }: CredentialsFormProps) {
   function getConnectionConfig(
     data: z.infer<typeof configSchema>,
   ): ConnectionConfig {
-    const { label, secretAccessKey, accessKeyId } = data;
+    const { label, secretAccessKey, accessKeyId, publicBaseUrl } = data;
 
     const configMap: Record<BucketProvider, () => ConnectionConfig> = {
       S3: () => {
@@ -170,6 +171,7 @@ export function CredentialsForm({ className }: CredentialsFormProps) {
               label,
               secret_access_key: secretAccessKey,
               access_key_id: accessKeyId,
+              public_base_url: publicBaseUrl || null,
             },
           },
         };
@@ -186,6 +188,7 @@ export function CredentialsForm({ className }: CredentialsFormProps) {
               label,
               secret_access_key: secretAccessKey,
               access_key_id: accessKeyId,
+              public_base_url: publicBaseUrl || null,
             },
             account_id: r2AccountId,
           },
@@ -203,6 +206,7 @@ export function CredentialsForm({ className }: CredentialsFormProps) {
               label,
               secret_access_key: secretAccessKey,
               access_key_id: accessKeyId,
+              public_base_url: publicBaseUrl || null,
             },
             endpoint_url: endpointUrl,
           },
@@ -347,6 +351,7 @@ export function CredentialsForm({ className }: CredentialsFormProps) {
                 label: watch("label"),
                 accessKeyId: watch("accessKeyId"),
                 secretAccessKey: watch("secretAccessKey"),
+                publicBaseUrl: watch("publicBaseUrl"),
               });
             }}
           >
@@ -437,6 +442,19 @@ export function CredentialsForm({ className }: CredentialsFormProps) {
           </FormField>
         )}
 
+        <FormField hasError={!!errors.publicBaseUrl}>
+          <label htmlFor="publicBaseUrl">Public Base URL (optional)</label>
+
+          <Input
+            type="text"
+            id="publicBaseUrl"
+            placeholder="https://cdn.example.com"
+            {...register("publicBaseUrl")}
+          />
+
+          <FormField.Error>Valid URL is required</FormField.Error>
+        </FormField>
+
         {isError && <p className="text-rose-500">Connection failed</p>}
 
         <Button type="submit" variant="outline" disabled={isPending}>
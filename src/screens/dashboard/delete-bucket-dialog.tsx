@@ -0,0 +1,315 @@
+import {
+  BucketInfo,
+  Connection,
+  EmptyAndDeleteBucketOptions,
+  EmptyBucketFinished,
+  EmptyBucketSummary,
+  events,
+} from "@/bindings";
+import { Button } from "@/components/ui/button";
+import {
+  Dialog,
+  DialogClose,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { Input } from "@/components/ui/input";
+import { CommandMap, useCommands } from "@/lib/use-commands";
+import { formatFileSize, isTauri } from "@/lib/utils";
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+
+interface DeleteBucketDialogProps {
+  isOpen: boolean;
+  onOpenChange: (open: boolean) => void;
+  onDeleted: () => void;
+  connection: Connection;
+  bucket: BucketInfo;
+}
+
+const emptySummary: EmptyBucketSummary = {
+  versioned: false,
+  object_count: 0,
+  noncurrent_version_count: 0,
+  delete_marker_count: 0,
+  total_size: 0,
+  multipart_upload_count: 0,
+};
+
+// Starts an empty-bucket job and resolves with its finished event. The
+// listeners are registered before the job starts so no event is missed.
+async function runEmptyBucketJob(
+  commands: CommandMap,
+  opts: EmptyAndDeleteBucketOptions,
+  onProgress: (summary: EmptyBucketSummary) => void,
+  onStarted?: (jobId: string) => void,
+): Promise<EmptyBucketFinished> {
+  if (!isTauri) {
+    const jobId = await commands.emptyAndDeleteBucket(opts);
+    onStarted?.(jobId);
+
+    return {
+      job_id: jobId,
+      summary: emptySummary,
+      dry_run: opts.dry_run,
+      bucket_deleted: !opts.dry_run,
+      errors: [],
+      cancelled: false,
+      error: null,
+    };
+  }
+
+  let jobId: string | null = null;
+  let finished: EmptyBucketFinished | null = null;
+  let resolveFinished: (event: EmptyBucketFinished) => void = () => {};
+
+  const done = new Promise<EmptyBucketFinished>((resolve) => {
+    resolveFinished = resolve;
+  });
+
+  const unlistenProgress = await events.emptyBucketProgress.listen((event) => {
+    if (event.payload.job_id === jobId) {
+      onProgress(event.payload.summary);
+    }
+  });
+
+  const unlistenFinished = await events.emptyBucketFinished.listen((event) => {
+    if (jobId === null) {
+      finished = event.payload;
+    } else if (event.payload.job_id === jobId) {
+      resolveFinished(event.payload);
+    }
+  });
+
+  try {
+    jobId = await commands.emptyAndDeleteBucket(opts);
+    onStarted?.(jobId);
+
+    // A dry run of a small bucket can finish before the id is returned
+    const early = finished as EmptyBucketFinished | null;
+    if (early?.job_id === jobId) {
+      return early;
+    }
+
+    return await done;
+  } finally {
+    unlistenProgress();
+    unlistenFinished();
+  }
+}
+
+export function DeleteBucketDialog({
+  isOpen,
+  onOpenChange,
+  onDeleted,
+  connection,
+  bucket,
+}: DeleteBucketDialogProps) {
+  const { commands } = useCommands();
+
+  const [summary, setSummary] = useState<EmptyBucketSummary | null>(null);
+  const [isScanning, setIsScanning] = useState(false);
+  const [isDeleting, setIsDeleting] = useState(false);
+  const [confirmation, setConfirmation] = useState("");
+
+  const options = (dryRun: boolean): EmptyAndDeleteBucketOptions => ({
+    common: {
+      connection,
+      bucket_region: bucket.region,
+    },
+    bucket_name: bucket.name,
+    dry_run: dryRun,
+    confirmation: dryRun ? null : confirmation,
+  });
+
+  // The dry run only reruns when the dialog is opened for another bucket
+  useEffect(() => {
+    if (!isOpen) {
+      return;
+    }
+
+    let isCurrent = true;
+    let dryRunJobId: string | null = null;
+
+    // A scan of a large bucket keeps listing after the dialog is closed or
+    // switched to another bucket unless it's cancelled
+    const cancelDryRun = (jobId: string) => {
+      commands.cancelJob(jobId).catch((error: unknown) => {
+        console.error(error);
+      });
+    };
+
+    setSummary(null);
+    setConfirmation("");
+    setIsScanning(true);
+
+    runEmptyBucketJob(
+      commands,
+      options(true),
+      (progress) => {
+        if (isCurrent) {
+          setSummary(progress);
+        }
+      },
+      (jobId) => {
+        dryRunJobId = jobId;
+
+        if (!isCurrent) {
+          cancelDryRun(jobId);
+        }
+      },
+    )
+      .then((finished) => {
+        if (!isCurrent) {
+          return;
+        }
+
+        if (finished.error) {
+          toast.error(`Failed to scan bucket: ${finished.error}`);
+          return;
+        }
+
+        setSummary(finished.summary);
+      })
+      .catch((error: unknown) => {
+        console.error(error);
+        toast.error("Failed to scan bucket.");
+      })
+      .finally(() => {
+        // A finished job is gone, there's nothing left to cancel
+        dryRunJobId = null;
+
+        if (isCurrent) {
+          setIsScanning(false);
+        }
+      });
+
+    return () => {
+      isCurrent = false;
+
+      if (dryRunJobId) {
+        cancelDryRun(dryRunJobId);
+      }
+    };
+  }, [isOpen, connection.id, bucket.name]);
+
+  const deleteBucket = async () => {
+    setIsDeleting(true);
+
+    try {
+      const finished = await runEmptyBucketJob(
+        commands,
+        options(false),
+        setSummary,
+      );
+
+      if (finished.bucket_deleted) {
+        toast.success("Bucket deleted successfully.");
+        onOpenChange(false);
+        onDeleted();
+        return;
+      }
+
+      setSummary(finished.summary);
+
+      if (finished.cancelled) {
+        toast.error("Bucket deletion was cancelled.");
+      } else {
+        const reason = finished.error ?? finished.errors[0];
+        toast.error(
+          reason
+            ? `Failed to delete bucket: ${reason}`
+            : "Failed to delete bucket.",
+        );
+      }
+    } catch (error) {
+      console.error(error);
+      toast.error("Failed to delete bucket.");
+    } finally {
+      setIsDeleting(false);
+    }
+  };
+
+  const canDelete =
+    !isScanning && !isDeleting && summary && confirmation === bucket.name;
+
+  return (
+    <Dialog
+      open={isOpen}
+      onOpenChange={(open) => {
+        if (!isDeleting) {
+          onOpenChange(open);
+        }
+      }}
+    >
+      <DialogContent>
+        <DialogHeader>
+          <DialogTitle>Delete bucket</DialogTitle>
+          <DialogDescription>
+            This permanently deletes {bucket.name} and everything in it. This
+            action cannot be undone.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="flex flex-col gap-1 text-sm">
+          {isScanning && <div>Scanning bucket…</div>}
+
+          {summary && (
+            <>
+              <div>Objects: {summary.object_count}</div>
+              {summary.versioned && (
+                <>
+                  <div>
+                    Noncurrent versions: {summary.noncurrent_version_count}
+                  </div>
+                  <div>Delete markers: {summary.delete_marker_count}</div>
+                </>
+              )}
+              <div>Total size: {formatFileSize(summary.total_size)}</div>
+              <div>
+                Incomplete multipart uploads: {summary.multipart_upload_count}
+              </div>
+            </>
+          )}
+        </div>
+
+        <div className="flex flex-col gap-2 text-sm">
+          <label htmlFor="delete-bucket-confirmation">
+            Type <span className="font-semibold">{bucket.name}</span> to
+            confirm.
+          </label>
+          <Input
+            id="delete-bucket-confirmation"
+            autoComplete="off"
+            value={confirmation}
+            disabled={isDeleting}
+            onChange={(e) => {
+              setConfirmation(e.target.value);
+            }}
+          />
+        </div>
+
+        <DialogFooter>
+          <DialogClose asChild>
+            <Button variant="outline" disabled={isDeleting}>
+              Cancel
+            </Button>
+          </DialogClose>
+
+          <Button
+            variant="destructive"
+            disabled={!canDelete}
+            onClick={() => {
+              void deleteBucket();
+            }}
+          >
+            {isDeleting ? "Deleting…" : "Delete bucket"}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
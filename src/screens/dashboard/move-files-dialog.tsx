@@ -12,6 +12,7 @@ import {
   DialogTitle,
 } from "@/components/ui/dialog";
 import { useCommands } from "@/lib/use-commands";
+import { visibleKey } from "@/lib/utils";
 import { useQuery } from "@tanstack/react-query";
 import { Folder } from "lucide-react";
 import { useState } from "react";
@@ -65,6 +66,9 @@ export function MoveFilesDialog({
         },
         bucket_name: bucket.name,
         prefix: dialogPrefix,
+        // Destinations are always slash separated folders
+        delimiter: null,
+        flat: false,
       });
 
       return result;
@@ -73,7 +77,9 @@ export function MoveFilesDialog({
   });
 
   const folders: FolderListItem[] = objects
-    .filter((object: ObjectInfo) => object.is_folder)
+    .filter(
+      (object: ObjectInfo) => object.is_folder && object.key !== dialogPrefix,
+    )
     .map((folder: ObjectInfo) => {
       const { key } = folder;
 
@@ -81,7 +87,9 @@ export function MoveFilesDialog({
       const labelWithPrefix = key.substring(0, key.length - 1);
 
       // Only display name relative to prefix instead of fully qualified name
-      const label = labelWithPrefix.substring(dialogPrefix?.length ?? 0);
+      const label = visibleKey(
+        labelWithPrefix.substring(dialogPrefix?.length ?? 0),
+      );
 
       return {
         key,
@@ -10,8 +10,13 @@ import {
   DropdownMenuTrigger,
 } from "@/components/ui/dropdown-menu";
 import { copyToClipboard } from "@/lib/actions";
+import { DELIMITERS, useListingSettings } from "@/lib/listing-settings";
 import { useCommands } from "@/lib/use-commands";
-import { formatFileSize, relativeTimeSince } from "@/lib/utils";
+import {
+  formatFileSize,
+  relativeTimeSince,
+  visibleKey,
+} from "@/lib/utils";
 import { useMutation, useQuery } from "@tanstack/react-query";
 import { basename, extname, join } from "@tauri-apps/api/path";
 import { open } from "@tauri-apps/plugin-dialog";
@@ -31,6 +36,7 @@ interface ObjectListItem {
   key: string;
   label: string;
   type: FileType;
+  hasMarker: boolean;
   size: number | null;
   lastModifiedAt: string | null;
   content?: ReactNode;
@@ -54,6 +60,12 @@ export function ObjectList({ connection, bucket }: BucketViewProps) {
     searchPhrase,
   } = useDashboardContext();
 
+  const { listingSettings, setListingSettings } = useListingSettings(
+    connection,
+    bucket,
+  );
+  const { delimiter, flat } = listingSettings;
+
   const [selectedObjects, setSelectedObjects] = useState<string[]>([]);
   const hasActiveSelection = !!selectedObjects.length;
 
@@ -68,10 +80,12 @@ export function ObjectList({ connection, bucket }: BucketViewProps) {
   >(null);
 
   // Displays the current folder as "bucketname / folder / nested"
-  const segments = prefix ? prefix.split("/").filter(Boolean) : [];
+  const segments = prefix ? prefix.split(delimiter).filter(Boolean) : [];
   const parentSegments = segments.slice(0, segments.length - 1);
   const parentPrefix =
-    parentSegments.length > 0 ? `${parentSegments.join("/")}/` : null;
+    parentSegments.length > 0
+      ? `${parentSegments.join(delimiter)}${delimiter}`
+      : null;
 
   const {
     data: objects,
@@ -79,7 +93,15 @@ export function ObjectList({ connection, bucket }: BucketViewProps) {
     isError,
     refetch,
   } = useQuery({
-    queryKey: ["buckets", connection.id, bucket.region, bucket.name, prefix],
+    queryKey: [
+      "buckets",
+      connection.id,
+      bucket.region,
+      bucket.name,
+      prefix,
+      delimiter,
+      flat,
+    ],
 
     queryFn: async () => {
       return await commands.listObjects({
@@ -89,30 +111,44 @@ export function ObjectList({ connection, bucket }: BucketViewProps) {
         },
         bucket_name: bucket.name,
         prefix,
+        delimiter,
+        flat,
       });
     },
 
     select: (objects: ObjectInfo[]): ObjectListItem[] => {
       return objects
         .filter((object) => {
+          // The marker object of the current folder is always shown
+          if (object.kind === "FolderMarker" && object.key === prefix) {
+            return true;
+          }
+
           return (
             !searchPhrase ||
             object.key.toLowerCase().includes(searchPhrase.toLowerCase())
           );
         })
         .map((file) => {
-          const { key, size, last_modified, is_folder, url } = file;
+          const { key, size, last_modified, is_folder, kind, url } = file;
+
+          // The marker of the current folder is listed like a file, so its
+          // size and metadata can be inspected in the preview
+          const isCurrentMarker = kind === "FolderMarker" && key === prefix;
+          const isFolder = is_folder && !isCurrentMarker;
 
-          // Hide slash suffix for folders
-          const labelWithPrefix = is_folder
-            ? key.substring(0, key.length - 1)
+          // Hide delimiter suffix for folders, markers end with it as well
+          const labelWithPrefix = isFolder
+            ? key.substring(0, key.length - delimiter.length)
             : key;
 
           // Only display name relative to prefix instead of fully qualified name
-          const label = labelWithPrefix.substring(prefix?.length ?? 0);
+          const label = isCurrentMarker
+            ? "(folder object)"
+            : visibleKey(labelWithPrefix.substring(prefix?.length ?? 0));
 
           function onClick() {
-            if (is_folder) {
+            if (isFolder) {
               setPrefix(key);
               setPreviewedObject(null);
               return;
@@ -126,7 +162,8 @@ export function ObjectList({ connection, bucket }: BucketViewProps) {
           return {
             key,
             label,
-            type: is_folder ? "folder" : "file",
+            type: isFolder ? "folder" : "file",
+            hasMarker: kind === "FolderMarker",
             size,
             lastModifiedAt: last_modified,
             url,
@@ -408,10 +445,17 @@ export function ObjectList({ connection, bucket }: BucketViewProps) {
 
                 <span className="flex grow items-center gap-2 truncate">
                   {icon} {item.label}
+                  {item.hasMarker && (
+                    <span className="rounded border px-1 text-xs text-neutral-500">
+                      marker
+                    </span>
+                  )}
                 </span>
 
                 <span className="min-w-20 text-center">
-                  {item.size ? formatFileSize({ bytes: item.size }) : ""}
+                  {item.size !== null && (item.size > 0 || item.hasMarker)
+                    ? formatFileSize({ bytes: item.size })
+                    : ""}
                 </span>
 
                 <div className="min-w-40 truncate">
@@ -554,6 +598,38 @@ export function ObjectList({ connection, bucket }: BucketViewProps) {
                 createFolder(folderName);
               }}
             />
+
+            <DropdownMenu>
+              <DropdownMenuTrigger asChild>
+                <Button variant="ghost">
+                  {flat ? "Flat list" : `Delimiter ${delimiter}`}
+                </Button>
+              </DropdownMenuTrigger>
+              <DropdownMenuContent>
+                {DELIMITERS.map((option) => {
+                  return (
+                    <DropdownMenuItem
+                      key={option}
+                      onClick={() => {
+                        setListingSettings({ delimiter: option, flat: false });
+                        setPrefix(null);
+                      }}
+                    >
+                      Delimiter {option}
+                    </DropdownMenuItem>
+                  );
+                })}
+
+                <DropdownMenuItem
+                  onClick={() => {
+                    setListingSettings({ delimiter, flat: true });
+                    setPrefix(null);
+                  }}
+                >
+                  Flat list
+                </DropdownMenuItem>
+              </DropdownMenuContent>
+            </DropdownMenu>
           </>
         )}
       </div>